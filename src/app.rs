@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+use directories::ProjectDirs;
+use crate::{
+    config::{ConfigData, ConfigFile, ConfigFind, DirKind},
+    format::ConfigFormat,
+};
+
+
+/// The configuration directory of an application, which may hold several
+///     distinct configuration files.
+///
+/// The directory is resolved once, by [`ProjectDirs`], when this is created,
+///     so that each file within it may be found by its filename alone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppConfigDir {
+    dir: PathBuf,
+}
+
+impl AppConfigDir {
+    /// Resolve the configuration directory of an application. Returns [`None`]
+    ///     if no valid home directory could be found.
+    ///
+    /// Arguments passed to this function are the same as the first three
+    ///     arguments of [`ConfigData::find`].
+    pub fn new(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+    ) -> Option<Self> {
+        Self::with_kind(DirKind::Config, qualifier, organization, application)
+    }
+
+    /// Resolve a specific kind of configuration directory of an application.
+    pub fn with_kind(
+        kind: DirKind,
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+    ) -> Option<Self> {
+        let dirs = ProjectDirs::from(qualifier, organization, application)?;
+        Some(Self { dir: kind.select(&dirs).to_owned() })
+    }
+
+    /// Return the path of the directory.
+    pub fn path(&self) -> &Path { &self.dir }
+
+    /// Return the path of a file within the directory.
+    pub fn file_path(&self, file: &str) -> PathBuf { self.dir.join(file) }
+
+    /// Find and read a configuration file within the directory, as with
+    ///     [`ConfigData::find`].
+    ///
+    /// The format is usually inferred, so the configuration type may be named
+    ///     as `dir.file::<Settings, _>("settings.toml")`.
+    pub fn file<Cfg, F>(&self, file: &str) -> ConfigFind<Cfg> where
+        Cfg: ConfigData<F>,
+        F: ConfigFormat,
+    {
+        Cfg::from_path(self.file_path(file))
+    }
+
+    /// Attempt to automatically handle all aspects of setup for a
+    ///     configuration file within the directory, as with
+    ///     [`ConfigData::setup`].
+    pub fn setup<Cfg, F>(
+        &self,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Cfg>), String> where
+        Cfg: ConfigData<F>,
+        F: ConfigFormat,
+    {
+        Cfg::setup_in(&self.dir, file)
+    }
+}
@@ -0,0 +1,77 @@
+//! Serde helpers for storing a [`Duration`] as a plain integer number of
+//!     seconds, such as `timeout = 30`.
+//!
+//! This module is intended to be used with the `with` attribute of Serde:
+//!
+//! ```
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//!
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Timeouts {
+//!     #[serde(with = "tomlconf::as_seconds")]
+//!     connect: Duration,
+//! }
+//!
+//!
+//! let parsed: Timeouts = toml::from_str("connect = 90").unwrap();
+//! assert_eq!(parsed.connect, Duration::from_secs(90));
+//!
+//! let text = toml::to_string(&parsed).unwrap();
+//! assert_eq!(text.trim(), "connect = 90");
+//! ```
+//!
+//! Any fraction of a second is discarded when a [`Duration`] is serialized.
+
+use std::time::Duration;
+use serde::{Deserialize, Deserializer, Serializer};
+
+
+/// Serialize a [`Duration`] as a whole number of seconds.
+pub fn serialize<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+
+/// Deserialize a [`Duration`] from a whole number of seconds.
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Timeout {
+        #[serde(with = "super")]
+        timeout: Duration,
+    }
+
+    #[test]
+    fn serialize_seconds() {
+        let value = Timeout { timeout: Duration::from_secs(90) };
+        assert_eq!(toml::to_string(&value).unwrap().trim(), "timeout = 90");
+    }
+
+    #[test]
+    fn deserialize_seconds() {
+        let parsed: Timeout = toml::from_str("timeout = 90").unwrap();
+        assert_eq!(parsed.timeout, Duration::from_secs(90));
+        assert!(toml::from_str::<Timeout>("timeout = -1").is_err());
+    }
+
+    #[test]
+    fn serialize_truncates_fraction() {
+        let value = Timeout { timeout: Duration::from_millis(2999) };
+        assert_eq!(toml::to_string(&value).unwrap().trim(), "timeout = 2");
+    }
+}
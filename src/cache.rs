@@ -0,0 +1,44 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+
+type Cache = Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>;
+
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+
+/// Return a clone of the value cached under the type `K`, first initializing it
+///     if it is not yet present. If initialization fails, nothing is cached.
+pub(crate) fn cached<K, T, E>(
+    init: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> where
+    K: ?Sized + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    let cache = CACHE.get_or_init(Default::default);
+    let key = TypeId::of::<K>();
+
+    let found = cache.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+        .and_then(|value| value.downcast_ref::<T>())
+        .cloned();
+
+    if let Some(value) = found {
+        return Ok(value);
+    }
+
+    //  Initialize without holding the lock, in case initialization requires
+    //      another cached value.
+    let value = init()?;
+
+    cache.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(key)
+        .or_insert_with(|| Box::new(value.clone()));
+
+    Ok(value)
+}
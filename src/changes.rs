@@ -0,0 +1,81 @@
+use serde::Serialize;
+use toml::Value;
+
+
+/// Join a key onto the dotted path of the table containing it.
+fn join(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_owned(),
+        false => format!("{}.{}", path, key),
+    }
+}
+
+
+/// Record the path of every value which differs between two values. Tables are
+///     compared key by key; Any other value, including an array, is compared
+///     as a whole.
+fn compare(path: &str, old: &Value, new: &Value, out: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Table(old), Value::Table(new)) => {
+            for (key, value) in old {
+                match new.get(key) {
+                    Some(other) => compare(&join(path, key), value, other, out),
+                    None => out.push(join(path, key)),
+                }
+            }
+
+            for key in new.keys().filter(|key| !old.contains_key(*key)) {
+                out.push(join(path, key));
+            }
+        }
+        (old, new) if old != new => out.push(path.to_owned()),
+        _ => {}
+    }
+}
+
+
+/// Compare two configurations, and return the dotted path of each field whose
+///     value differs between them, such as `database.port`.
+///
+/// A field which is present in only one of the configurations, such as an
+///     [`Option`] which is [`None`] in the other, is also included. Arrays are
+///     compared as a whole, so a change to any element reports the path of
+///     the array itself.
+///
+/// This may be used after a reload to decide which parts of a program need to
+///     be restarted. An error is returned if either configuration cannot be
+///     represented as TOML.
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+/// use tomlconf::config_diff;
+///
+/// #[derive(Serialize)]
+/// struct Database { host: String, port: u16 }
+///
+/// #[derive(Serialize)]
+/// struct Config { name: String, database: Database }
+///
+/// let old = Config {
+///     name: "app".into(),
+///     database: Database { host: "localhost".into(), port: 5432 },
+/// };
+/// let new = Config {
+///     name: "app".into(),
+///     database: Database { host: "localhost".into(), port: 5433 },
+/// };
+///
+/// assert_eq!(config_diff(&old, &new).unwrap(), ["database.port"]);
+/// assert!(config_diff(&old, &old).unwrap().is_empty());
+/// ```
+pub fn config_diff<Cfg: Serialize + ?Sized>(
+    old: &Cfg,
+    new: &Cfg,
+) -> Result<Vec<String>, toml::ser::Error> {
+    let mut out = Vec::new();
+
+    compare("", &Value::try_from(old)?, &Value::try_from(new)?, &mut out);
+    Ok(out)
+}
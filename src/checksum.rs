@@ -0,0 +1,51 @@
+use std::{ffi::OsString, fmt::Write, io::ErrorKind, path::{Path, PathBuf}};
+use sha2::{Digest, Sha256};
+
+
+/// Return the path of the checksum file kept alongside a configuration file.
+fn sidecar(path: &Path) -> PathBuf {
+    let mut name: OsString = path.file_name().unwrap_or_default().to_owned();
+    name.push(".sha256");
+
+    path.with_file_name(name)
+}
+
+
+/// Compute the SHA-256 digest of some bytes, as a lowercase hexadecimal string.
+fn digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().fold(
+        String::with_capacity(64),
+        |mut hex, byte| {
+            write!(hex, "{:02x}", byte).ok();
+            hex
+        },
+    )
+}
+
+
+/// Write a checksum file for the bytes written into the file at a path.
+///
+/// The digest covers the bytes of the file as they are on disk, compressed or
+///     not, and the file uses the same format as the output of `sha256sum`,
+///     so it may also be checked with `sha256sum -c`.
+pub(crate) fn write(path: &Path, bytes: &[u8]) -> Result<(), std::io::Error> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    std::fs::write(sidecar(path), format!("{}  {}\n", digest(bytes), name))
+}
+
+
+/// Check whether the bytes read from the file at a path match the checksum
+///     file alongside it. If there is no checksum file, the bytes are
+///     considered valid.
+pub(crate) fn verify(
+    path: &Path,
+    bytes: &[u8],
+) -> Result<bool, std::io::Error> {
+    match std::fs::read_to_string(sidecar(path)) {
+        Ok(saved) => {
+            Ok(saved.split_whitespace().next() == Some(&digest(bytes)))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e),
+    }
+}
@@ -1,24 +1,54 @@
 use std::{
-    ffi::OsString,
+    borrow::Cow,
     fmt::{Display, Formatter, self},
-    fs::{create_dir_all, File, rename},
-    io::{Read, Seek, SeekFrom, Write},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
 };
+#[cfg(feature = "fs")]
+use std::{
+    ffi::OsString,
+    fs::{OpenOptions, rename},
+    io::Write,
+};
+#[cfg(feature = "directories")]
 use directories::ProjectDirs;
+#[cfg(feature = "directories")]
+use crate::names::{Application, FileName, Organization, Qualifier};
 use serde::{de::DeserializeOwned, Serialize};
+use crate::{
+    env::{EnvBinding, EnvError},
+    format::{ConfigFormat, FormatError, ParseError, Toml},
+    options::SaveOptions,
+    storage::{ConfigStorage, InMemory},
+    warning::Warning,
+};
+#[cfg(feature = "fs")]
+use crate::{
+    entry::ConfigEntry,
+    options::BackupPolicy,
+    storage::{create_dir, encode, resolve_link},
+    strategy::PathStrategy,
+};
+#[cfg(feature = "directories")]
+use crate::strategy::InProjectDirs;
+
+
+/// A placeholder to be used in place of sensitive values when producing a
+///     redacted view of a configuration.
+pub const REDACTED: &str = "***";
 
 
 /// Locate the path of the configuration file.
-fn find_path(
+#[cfg(feature = "directories")]
+pub(crate) fn find_path(
+    kind: DirKind,
     qualifier: &str,
     organization: &str,
     application: &str,
     filename: &str,
 ) -> Option<PathBuf> {
     let dirs = ProjectDirs::from(qualifier, organization, application)?;
-    let mut path: PathBuf = dirs.config_dir().to_owned();
+    let mut path: PathBuf = kind.select(&dirs).to_owned();
 
     path.push(filename);
     Some(path)
@@ -26,26 +56,307 @@ fn find_path(
 
 
 /// Given a path, return a new path where a file at the first path may be moved
-///     to save as a backup.
-fn get_backup(path: &Path) -> Option<PathBuf> {
+///     to save as a backup. If a directory is given, the backup is placed into
+///     it, relative to the directory containing the file.
+///
+/// Usually, the backup is hidden by a prefix, so that it keeps the extension of
+///     the original file: `config.local.toml` is saved as
+///     `.bkp.config.local.toml`. A name which is already hidden, or has no
+///     extension, is given a suffix instead: `.myapprc` is saved as
+///     `.myapprc.bkp`, and `config` as `config.bkp`.
+#[cfg(feature = "fs")]
+fn get_backup(path: &Path, dir: Option<&Path>) -> Option<PathBuf> {
     const PREFIX: &str = ".bkp.";
+    const SUFFIX: &str = ".bkp";
 
     let name = path.file_name()?;
     let mut backup = OsString::with_capacity(PREFIX.len() + name.len());
 
-    backup.push(PREFIX);
-    backup.push(name);
+    if name.to_string_lossy().starts_with('.') || path.extension().is_none() {
+        backup.push(name);
+        backup.push(SUFFIX);
+    } else {
+        backup.push(PREFIX);
+        backup.push(name);
+    }
+
+    match dir {
+        Some(dir) => {
+            let parent = path.parent().unwrap_or_else(|| Path::new(""));
+            Some(parent.join(dir).join(backup))
+        }
+        None => Some(path.with_file_name(backup)),
+    }
+}
+
+
+/// Choose the path at which to save a backup of the file at the given path,
+///     following the [`BackupPolicy`] if a backup already exists there. Return
+///     [`None`] if no backup should be saved.
+#[cfg(feature = "fs")]
+fn backup_target(path: &Path, opts: &SaveOptions) -> Option<PathBuf> {
+    let backup = get_backup(path, opts.backup_dir.as_deref())?;
+
+    if backup.symlink_metadata().is_err() {
+        return Some(backup);
+    }
+
+    match opts.backup_policy {
+        BackupPolicy::Overwrite => Some(backup),
+        BackupPolicy::Skip => None,
+        BackupPolicy::Suffix => (1..).map(|n| {
+            let mut name = backup.file_name().unwrap_or_default().to_owned();
+
+            name.push(format!(".{}", n));
+            backup.with_file_name(name)
+        }).find(|path| path.symlink_metadata().is_err()),
+    }
+}
+
+
+/// Return an error if any directory above the given path is a symbolic link
+///     whose target does not exist, which would otherwise cause a confusing
+///     failure when trying to create the directory.
+#[cfg(feature = "fs")]
+fn reject_broken_link(path: &Path) -> Result<(), std::io::Error> {
+    for dir in path.ancestors().skip(1) {
+        let is_link = dir.symlink_metadata()
+            .is_ok_and(|meta| meta.file_type().is_symlink());
+
+        if is_link && !dir.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "Config directory is a broken symbolic link: {}",
+                    dir.display(),
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Prepare to write a file at the given path, by moving any existing file to a
+///     backup location or creating the parent directory, as requested.
+#[cfg(feature = "fs")]
+pub(crate) fn prepare_write(path: &Path, opts: &SaveOptions) -> Result<(), std::io::Error> {
+    reject_dir(path)?;
+    reject_broken_link(path)?;
+
+    if opts.create_backup && !opts.atomic && path.exists() {
+        if let Some(backup) = backup_target(path, opts) {
+            if let Some(dir) = backup.parent() {
+                create_dir(dir, opts).ok();
+            }
+
+            rename(path, backup).ok();
+        }
+    } else if opts.create_parent {
+        if let Some(parent) = path.parent() {
+            if !parent.exists() {
+                create_dir(parent, opts)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Save a backup of any existing file at the given path, as requested, while
+///     leaving the file itself in place. An atomic write does this only once
+///     the new text has been written in full, so that a failure cannot leave
+///     the file missing.
+#[cfg(feature = "fs")]
+pub(crate) fn link_backup(path: &Path, opts: &SaveOptions) {
+    use std::fs::{copy, hard_link, remove_file};
+
+    if opts.create_backup && path.exists() {
+        if let Some(backup) = backup_target(path, opts) {
+            if let Some(dir) = backup.parent() {
+                create_dir(dir, opts).ok();
+            }
+
+            remove_file(&backup).ok();
+
+            if hard_link(path, &backup).is_err() {
+                copy(path, &backup).ok();
+            }
+        }
+    }
+}
+
+
+/// Return an error if the given path is an existing directory, which cannot be
+///     written as a configuration file.
+#[cfg(feature = "fs")]
+pub(crate) fn reject_dir(path: &Path) -> Result<(), std::io::Error> {
+    if path.is_dir() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::IsADirectory,
+            format!(
+                "Config path is a directory, not a file: {}",
+                path.display(),
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+
+/// Describe the failure to open a configuration file during setup, in a form
+///     suitable for immediate delivery to the user.
+#[cfg(feature = "fs")]
+fn open_error<Cfg>(path: &Path, open: ConfigOpen<Cfg>) -> String {
+    match open {
+        ConfigOpen::FileInaccessible(e) => format!(
+            "Cannot access {} as Config file: {}",
+            path.display(), e,
+        ),
+        ConfigOpen::FileInvalid(e) => format!(
+            "Cannot read {} as Config file: {}",
+            path.display(), e,
+        ),
+        ConfigOpen::EnvInvalid(e) => format!(
+            "Cannot use {} as Config file: {}",
+            path.display(), e,
+        ),
+        ConfigOpen::UnknownKeys(keys) => format!(
+            "Cannot use {} as Config file: Unknown keys: {}.",
+            path.display(), keys.join(", "),
+        ),
+        ConfigOpen::FileEmpty => format!(
+            "Cannot read {} as Config file: File is empty.",
+            path.display(),
+        ),
+        ConfigOpen::ChecksumMismatch => format!(
+            "Cannot verify {} as Config file: Checksum does not match.",
+            path.display(),
+        ),
+        ConfigOpen::FileValid(..) => format!(
+            "Successfully read {} as Config file.",
+            path.display(),
+        ),
+    }
+}
+
+
+/// Insert the name of a profile into a filename, before its extension.
+#[cfg(feature = "directories")]
+fn profile_name(file: &str, profile: &str) -> String {
+    match file.rfind('.') {
+        _ if profile.is_empty() => file.to_owned(),
+        Some(idx) if idx > 0 => {
+            format!("{}.{}{}", &file[..idx], profile, &file[idx..])
+        }
+        _ => format!("{}.{}", file, profile),
+    }
+}
+
+
+/// Check whether the text of a file is empty. If it is, return the default text
+///     if the configuration allows it, or otherwise [`None`].
+fn fill_empty<Cfg, F>(text: String) -> Option<String> where
+    Cfg: ConfigData<F>,
+    F: ConfigFormat,
+{
+    match text.trim().is_empty() {
+        false => Some(text),
+        true if Cfg::EMPTY_IS_DEFAULT => Some(Cfg::DEFAULT.to_owned()),
+        true => None,
+    }
+}
+
+
+/// Complete configuration setup from the result of a search, using the given
+///     function to open a newly created file.
+#[cfg(feature = "fs")]
+fn complete_setup<Cfg, F>(
+    found: ConfigFind<Cfg>,
+    open: fn(&Path) -> ConfigOpen<Cfg>,
+) -> Result<(SetupOutcome, ConfigFile<Cfg>), String> where
+    Cfg: ConfigData<F>,
+    F: ConfigFormat,
+{
+    use ConfigFind::*;
+
+    match found {
+        DoesNotExist(path) => match Cfg::create(&path, true, true) {
+            Err(e) => Err(format!(
+                "Cannot save {} as Config file: {}",
+                path.display(), e,
+            )),
+            Ok(..) => match open(&path) {
+                ConfigOpen::FileValid(cfg) => Ok((
+                    SetupOutcome::Created(path.clone()),
+                    cfg.with_path(path),
+                )),
+                err => Err(open_error(&path, err)),
+            }
+        }
+        Exists(path, cfg) => match cfg {
+            ConfigOpen::FileValid(cfg) => Ok((
+                SetupOutcome::Existing(path.clone()),
+                cfg.with_path(path),
+            )),
+            err => Err(open_error(&path, err)),
+        }
+        Inaccessible(path, e) => {
+            Err(open_error(&path, ConfigOpen::<Cfg>::FileInaccessible(e)))
+        }
+        NoPath => Err(String::from("Cannot find path for Config file.")),
+    }
+}
+
+
+/// The kind of platform directory in which a configuration file is placed.
+///
+/// The distinction only matters on Windows, where the user profile is split
+///     into a "Roaming" portion, which may be synchronized between machines,
+///     and a "Local" portion, which is specific to one machine. On all other
+///     platforms, both kinds resolve to the same directory.
+#[cfg(feature = "directories")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DirKind {
+    /// The standard configuration directory, given by
+    ///     [`ProjectDirs::config_dir`]. On Windows, this is in the Roaming
+    ///     AppData folder.
+    #[default]
+    Config,
+    /// The machine-local configuration directory, given by
+    ///     [`ProjectDirs::config_local_dir`]. On Windows, this is in the Local
+    ///     AppData folder.
+    ConfigLocal,
+}
 
-    Some(path.with_file_name(backup))
+#[cfg(feature = "directories")]
+impl DirKind {
+    /// Select the directory of this kind from a set of project directories.
+    pub fn select(self, dirs: &ProjectDirs) -> &Path {
+        match self {
+            Self::Config => dirs.config_dir(),
+            Self::ConfigLocal => dirs.config_local_dir(),
+        }
+    }
 }
 
 
 /// The result of trying to find a configuration file.
+///
+/// More kinds of failure may be added in future versions, so a `match` on this
+///     type must include a wildcard arm.
+#[non_exhaustive]
 pub enum ConfigFind<Cfg> {
     /// The file does not exist.
     DoesNotExist(PathBuf),
     /// The file does exist; Also includes the result of attempting to load it.
     Exists(PathBuf, ConfigOpen<Cfg>),
+    /// It could not be determined whether the file exists, usually because
+    ///     the directory containing it cannot be accessed.
+    Inaccessible(PathBuf, std::io::Error),
     /// No path was found at which to search for a file.
     NoPath,
 }
@@ -76,13 +387,25 @@ impl<Cfg> ConfigFind<Cfg> {
         }
     }
 
+    /// Get the configuration inside this value, if it was found and opened
+    ///     successfully, or otherwise a [`ConfigError`] describing the failure.
+    pub fn into_config_result(self) -> Result<Cfg, ConfigError> {
+        match self {
+            Self::DoesNotExist(path) => Err(ConfigError::DoesNotExist(path)),
+            Self::Exists(_, open) => open.into_config_result(),
+            Self::Inaccessible(_, e) => Err(ConfigError::FileInaccessible(e)),
+            Self::NoPath => Err(ConfigError::NoPath),
+        }
+    }
+
     /// Return a reference to the filepath checked by the search operation, if
     ///     there was one.
     pub fn path(&self) -> Option<&PathBuf> {
         match self {
             Self::NoPath => None,
             Self::Exists(path, _)
-            | Self::DoesNotExist(path) => Some(path),
+            | Self::DoesNotExist(path)
+            | Self::Inaccessible(path, _) => Some(path),
         }
     }
 }
@@ -97,18 +420,51 @@ impl<Cfg> Display for ConfigFind<Cfg> {
             Self::Exists(path, open) => {
                 write!(f, "{} at {}", open, path.display())
             }
+            Self::Inaccessible(path, e) => {
+                write!(f, "Cannot access file: {} at {}", e, path.display())
+            }
             Self::NoPath => f.write_str("Cannot find config path."),
         }
     }
 }
 
 
+/// Iterate over the configuration inside a [`ConfigFind`], yielding it once if
+///     it was opened successfully, and not at all otherwise, in the same way
+///     as an [`Option`].
+///
+/// Any failure is silently treated as an absence of configuration, so this is
+///     only suitable where the configuration is used on a best-effort basis.
+impl<Cfg> IntoIterator for ConfigFind<Cfg> {
+    type Item = Cfg;
+    type IntoIter = std::option::IntoIter<Cfg>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_config().into_iter()
+    }
+}
+
+
 /// The result of attempting to load a configuration from a filepath.
+///
+/// More kinds of failure may be added in future versions, so a `match` on this
+///     type must include a wildcard arm.
+#[non_exhaustive]
 pub enum ConfigOpen<Cfg> {
     /// The file could not be opened.
     FileInaccessible(std::io::Error),
     /// The file could not be parsed.
-    FileInvalid(toml::de::Error),
+    FileInvalid(ParseError),
+    /// The file does not match the checksum saved alongside it, and may have
+    ///     been corrupted.
+    ChecksumMismatch,
+    /// An environment variable bound to a field could not be parsed.
+    EnvInvalid(EnvError),
+    /// The file is empty, or contains only whitespace.
+    FileEmpty,
+    /// The file contains keys which the configuration does not declare. This
+    ///     is only returned by [`ConfigData::open_strict`].
+    UnknownKeys(Vec<String>),
     /// The file load was successful.
     FileValid(Cfg),
 }
@@ -138,6 +494,32 @@ impl<Cfg> ConfigOpen<Cfg> {
             err => Err(err),
         }
     }
+
+    /// Get a reference to the configuration inside this value, if it was opened
+    ///     successfully, or otherwise a reference to this value, as with
+    ///     [`into_result`], but without consuming it.
+    ///
+    /// [`into_result`]: Self::into_result
+    pub fn as_result(&self) -> Result<&Cfg, &Self> {
+        match self {
+            Self::FileValid(config) => Ok(config),
+            err => Err(err),
+        }
+    }
+
+    /// Get the configuration inside this value, if it was opened successfully,
+    ///     or otherwise a [`ConfigError`] describing the failure.
+    pub fn into_config_result(self) -> Result<Cfg, ConfigError> {
+        match self {
+            Self::FileInaccessible(e) => Err(ConfigError::FileInaccessible(e)),
+            Self::FileInvalid(e) => Err(ConfigError::FileInvalid(e)),
+            Self::ChecksumMismatch => Err(ConfigError::ChecksumMismatch),
+            Self::EnvInvalid(e) => Err(ConfigError::EnvInvalid(e)),
+            Self::FileEmpty => Err(ConfigError::FileEmpty),
+            Self::UnknownKeys(keys) => Err(ConfigError::UnknownKeys(keys)),
+            Self::FileValid(config) => Ok(config),
+        }
+    }
 }
 
 
@@ -150,12 +532,33 @@ impl<Cfg> Display for ConfigOpen<Cfg> {
             Self::FileInvalid(e) => {
                 write!(f, "Cannot read configuration: {}", e)
             }
+            Self::ChecksumMismatch => {
+                f.write_str("File does not match its checksum")
+            }
+            Self::EnvInvalid(e) => write!(f, "Cannot apply environment: {}", e),
+            Self::FileEmpty => f.write_str("File is empty"),
+            Self::UnknownKeys(keys) => {
+                write!(f, "File contains unknown keys: {}", keys.join(", "))
+            }
             Self::FileValid(..) => f.write_str("Successfully read file"),
         }
     }
 }
 
 
+/// Iterate over the configuration inside a [`ConfigOpen`], yielding it once if
+///     it was opened successfully, as with a [`ConfigFind`]. Failures are
+///     likewise silently skipped.
+impl<Cfg> IntoIterator for ConfigOpen<Cfg> {
+    type Item = Cfg;
+    type IntoIter = std::option::IntoIter<Cfg>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_config().into_iter()
+    }
+}
+
+
 #[cfg(feature = "nightly")]
 impl<Cfg> std::ops::FromResidual for ConfigOpen<Cfg> {
     fn from_residual(residual: <Self as std::ops::Try>::Residual) -> Self {
@@ -187,35 +590,346 @@ impl<Cfg> std::ops::Try for ConfigOpen<Cfg> {
 }
 
 
+/// The kind of action taken by a successful configuration setup, without the
+///     path of the file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SetupAction {
+    /// No file existed, so a new default file was created.
+    Created,
+    /// An existing file was found, and was used.
+    Existing,
+}
+
+impl SetupAction {
+    /// Return a short lowercase name for the action, either `"created"` or
+    ///     `"existing"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Existing => "existing",
+        }
+    }
+}
+
+
+/// The action taken by a successful configuration setup.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SetupOutcome {
+    /// No file existed, so a new default file was created at this path.
+    Created(PathBuf),
+    /// An existing file was found at this path, and was used.
+    Existing(PathBuf),
+}
+
+impl SetupOutcome {
+    /// Return a reference to the path of the file that was used.
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            Self::Created(path) | Self::Existing(path) => path,
+        }
+    }
+
+    /// Return the kind of action that was taken.
+    pub fn action(&self) -> SetupAction {
+        match self {
+            Self::Created(..) => SetupAction::Created,
+            Self::Existing(..) => SetupAction::Existing,
+        }
+    }
+
+    /// Separate the kind of action that was taken from the path of the file,
+    ///     so that each may be reported or translated independently.
+    pub fn into_parts(self) -> (SetupAction, PathBuf) {
+        let action = self.action();
+
+        match self {
+            Self::Created(path) | Self::Existing(path) => (action, path),
+        }
+    }
+
+    /// Render this outcome as a small JSON object, suitable for consumption by
+    ///     scripts. For example:
+    ///
+    /// ```json
+    /// {"action":"created","path":"/home/user/.config/app/config.toml"}
+    /// ```
+    ///
+    /// The action is either `"created"` or `"existing"`. A path which is not
+    ///     valid Unicode is converted lossily.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "action": self.action().as_str(),
+            "path": self.path().to_string_lossy(),
+        }).to_string()
+    }
+}
+
+
+impl Display for SetupOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Created(path) => {
+                write!(f, "Created new Config file: {}", path.display())
+            }
+            Self::Existing(path) => {
+                write!(f, "Using existing Config file: {}", path.display())
+            }
+        }
+    }
+}
+
+
+/// The result of an attempt to reload a configuration with
+///     [`ConfigFile::try_reload`].
+pub enum ReloadOutcome<Cfg> {
+    /// The file was read, and its configuration replaced the stored data.
+    Reloaded,
+    /// The file has not been modified since it was last read, so it was not
+    ///     read again, and the stored data was left untouched.
+    Unchanged,
+    /// The file could not be read, and the stored data was left untouched.
+    Failed(ConfigOpen<Cfg>),
+}
+
+impl<Cfg> ReloadOutcome<Cfg> {
+    /// Return `true` if the stored data was replaced.
+    pub fn is_reloaded(&self) -> bool { matches!(self, Self::Reloaded) }
+}
+
+
 /// An error returned when attempting to save a configuration into a file.
+///
+/// More kinds of failure may be added in future versions, so a `match` on this
+///     type must include a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConfigSaveError {
     /// The file could not be opened.
     FileInaccessible(std::io::Error),
+    /// The file could not be written because the storage device is full. If
+    ///     the [`atomic`] option was set, the existing file is unchanged.
+    ///
+    /// [`atomic`]: SaveOptions::atomic
+    DiskFull(std::io::Error),
     /// The data could not be converted.
-    SerializeFailure(toml::ser::Error),
+    SerializeFailure(FormatError),
+    /// The existing contents of the file could not be parsed.
+    FileInvalid(FormatError),
+}
+
+
+impl ConfigSaveError {
+    /// Return the kind of the underlying IO error, if the file could not be
+    ///     written, so that a failure may be explained to the user, such as
+    ///     [`PermissionDenied`] or [`ReadOnlyFilesystem`]. Return [`None`] if
+    ///     the data could not be converted.
+    ///
+    /// ```
+    /// use std::io::{Error, ErrorKind};
+    /// use tomlconf::ConfigSaveError;
+    ///
+    /// let denied = Error::from(ErrorKind::PermissionDenied);
+    /// let err = ConfigSaveError::from(denied);
+    ///
+    /// assert!(matches!(err, ConfigSaveError::FileInaccessible(..)));
+    /// assert_eq!(err.kind(), Some(ErrorKind::PermissionDenied));
+    /// ```
+    ///
+    /// [`PermissionDenied`]: std::io::ErrorKind::PermissionDenied
+    /// [`ReadOnlyFilesystem`]: std::io::ErrorKind::ReadOnlyFilesystem
+    pub fn kind(&self) -> Option<std::io::ErrorKind> {
+        self.io_error().map(std::io::Error::kind)
+    }
+
+    /// Return the underlying IO error, if the file could not be written.
+    pub fn io_error(&self) -> Option<&std::io::Error> {
+        match self {
+            Self::FileInaccessible(e) | Self::DiskFull(e) => Some(e),
+            Self::SerializeFailure(..) | Self::FileInvalid(..) => None,
+        }
+    }
 }
 
 
 impl From<std::io::Error> for ConfigSaveError {
-    fn from(e: std::io::Error) -> Self { Self::FileInaccessible(e) }
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::StorageFull => Self::DiskFull(e),
+            _ => Self::FileInaccessible(e),
+        }
+    }
 }
 
 
 impl From<toml::ser::Error> for ConfigSaveError {
-    fn from(e: toml::ser::Error) -> Self { Self::SerializeFailure(e) }
+    fn from(e: toml::ser::Error) -> Self { Self::SerializeFailure(e.into()) }
+}
+
+
+/// An error returned by any operation on a configuration.
+///
+/// This unifies the failures described by [`ConfigOpen`], [`ConfigSaveError`]
+///     and [`ConfigFind`]. A [`ConfigSaveError`] may be converted directly into
+///     this type, while [`ConfigOpen`] and [`ConfigFind`] each provide an
+///     `into_config_result` method.
+///
+/// More kinds of failure may be added in future versions, so a `match` on this
+///     type must include a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The file could not be accessed.
+    FileInaccessible(std::io::Error),
+    /// The file could not be parsed.
+    FileInvalid(ParseError),
+    /// The file does not match the checksum saved alongside it.
+    ChecksumMismatch,
+    /// An environment variable bound to a field could not be parsed.
+    EnvInvalid(EnvError),
+    /// The file is empty, or contains only whitespace.
+    FileEmpty,
+    /// The file contains keys which the configuration does not declare.
+    UnknownKeys(Vec<String>),
+    /// The data could not be converted.
+    SerializeFailure(FormatError),
+    /// The configuration was rejected by [`ConfigData::validate`].
+    ValidationFailed(String),
+    /// The file does not exist.
+    DoesNotExist(PathBuf),
+    /// No path could be determined for the file.
+    NoPath,
+}
+
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileInaccessible(e) => {
+                write!(f, "Cannot access file: {}", e)
+            }
+            Self::FileInvalid(e) => {
+                write!(f, "Cannot read configuration: {}", e)
+            }
+            Self::ChecksumMismatch => {
+                f.write_str("File does not match its checksum")
+            }
+            Self::EnvInvalid(e) => write!(f, "Cannot apply environment: {}", e),
+            Self::FileEmpty => f.write_str("File is empty"),
+            Self::UnknownKeys(keys) => {
+                write!(f, "File contains unknown keys: {}", keys.join(", "))
+            }
+            Self::SerializeFailure(e) => {
+                write!(f, "Cannot convert configuration: {}", e)
+            }
+            Self::ValidationFailed(msg) => {
+                write!(f, "Invalid configuration: {}", msg)
+            }
+            Self::DoesNotExist(path) => {
+                write!(f, "File does not exist: {}", path.display())
+            }
+            Self::NoPath => f.write_str("Cannot find config path."),
+        }
+    }
+}
+
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::FileInaccessible(e) => Some(e),
+            Self::FileInvalid(e) => Some(e),
+            Self::EnvInvalid(e) => Some(e),
+            Self::SerializeFailure(e) => Some(&**e),
+            Self::ChecksumMismatch
+            | Self::FileEmpty
+            | Self::UnknownKeys(..)
+            | Self::ValidationFailed(..)
+            | Self::DoesNotExist(..)
+            | Self::NoPath => None,
+        }
+    }
+}
+
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self { Self::FileInaccessible(e) }
+}
+
+
+impl From<ParseError> for ConfigError {
+    fn from(e: ParseError) -> Self { Self::FileInvalid(e) }
+}
+
+
+impl From<ConfigSaveError> for ConfigError {
+    fn from(e: ConfigSaveError) -> Self {
+        match e {
+            ConfigSaveError::FileInaccessible(e)
+            | ConfigSaveError::DiskFull(e) => Self::FileInaccessible(e),
+            ConfigSaveError::SerializeFailure(e) => Self::SerializeFailure(e),
+            ConfigSaveError::FileInvalid(e) => {
+                Self::FileInvalid(ParseError::from_error(e))
+            }
+        }
+    }
 }
 
 
 /// Implements a set of convenience functions for finding a configuration file
 ///     and deserializing it into a usable struct.
-pub trait ConfigData: DeserializeOwned {
+///
+/// The type parameter `F` selects the [`ConfigFormat`] in which the file is
+///     stored, and defaults to [`Toml`]. A type may implement this trait more
+///     than once in order to support several formats, in which case the format
+///     must be named when calling its methods.
+pub trait ConfigData<F: ConfigFormat = Toml>: DeserializeOwned {
     /// The text data of a default configuration file, as UTF-8. Ideally read
     ///     from a default file at compile time using the [`include_str`] macro.
+    ///
+    /// This text must be in the format selected by `F`.
     const DEFAULT: &'static str;
 
+    /// Whether a file which is empty, or contains only whitespace, should be
+    ///     read as though it contained [`DEFAULT`]. Otherwise, opening such a
+    ///     file results in [`ConfigOpen::FileEmpty`]. Defaults to `false`.
+    ///
+    /// An empty file is often left behind by a write which was interrupted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { name: String }
+    /// # #[derive(serde::Deserialize)] struct Lenient { name: String }
+    /// use tomlconf::{ConfigData, ConfigOpen};
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "name = 'default'";
+    /// }
+    ///
+    /// impl ConfigData for Lenient {
+    ///     const DEFAULT: &'static str = "name = 'default'";
+    ///     const EMPTY_IS_DEFAULT: bool = true;
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join("tomlconf-empty.toml");
+    /// std::fs::write(&path, "").unwrap();
+    ///
+    /// assert!(matches!(Config::open(&path), ConfigOpen::FileEmpty));
+    /// assert_eq!(Lenient::open(&path).into_config().unwrap().name, "default");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// [`DEFAULT`]: Self::DEFAULT
+    const EMPTY_IS_DEFAULT: bool = false;
+
     /// Create a default configuration file at the given path.
     ///
+    /// If the path refers to an existing directory, an error of the kind
+    ///     [`IsADirectory`] is returned, and nothing is moved or written.
+    ///
+    /// [`IsADirectory`]: std::io::ErrorKind::IsADirectory
+    ///
     /// # Arguments
     ///
     /// * `path`: The path at which to create the new file.
@@ -225,34 +939,216 @@ pub trait ConfigData: DeserializeOwned {
     ///     new file, if it does not exist.
     ///
     /// returns: `Result<(), std::io::Error>`
+    #[cfg(feature = "fs")]
     fn create(
         path: &Path,
         create_backup: bool,
         create_parent: bool,
     ) -> Result<(), std::io::Error> {
-        if create_backup && path.exists() {
-            if let Some(backup) = get_backup(path) {
-                rename(path, backup).ok();
-            }
-        } else if create_parent {
-            if let Some(parent) = path.parent() {
-                if !parent.exists() {
-                    create_dir_all(parent)?;
-                }
+        Self::create_with(path, &SaveOptions::new(create_backup, create_parent))
+    }
+
+    /// Create a default configuration file at the given path, as with
+    ///     [`create`], using a full set of options.
+    ///
+    /// [`create`]: Self::create
+    #[cfg(feature = "fs")]
+    fn create_with(path: &Path, opts: &SaveOptions) -> Result<(), std::io::Error> {
+        path.write(&Self::default_content(), opts)
+    }
+
+    /// Create a default configuration file at the given path, as with
+    ///     [`create`], and record the version of the application beside it, in
+    ///     a marker file as used by [`setup_if_outdated`].
+    ///
+    /// The marker is named after the file with `.version` appended, such as
+    ///     `config.toml.version`, and holds the version string on one line.
+    ///
+    /// [`create`]: Self::create
+    /// [`setup_if_outdated`]: Self::setup_if_outdated
+    #[cfg(feature = "fs")]
+    fn create_versioned(
+        path: &Path,
+        version: &str,
+    ) -> Result<(), std::io::Error> {
+        Self::create_with(
+            path,
+            &SaveOptions::default().with_backup_policy(BackupPolicy::Suffix),
+        )?;
+        crate::version::write_marker(path, version)
+    }
+
+    /// Create a default configuration file at the given path, only if no file
+    ///     exists there already. The parent directory is created if necessary.
+    ///
+    /// Returns `true` if a new file was created, or `false` if a file already
+    ///     existed, in which case it is left untouched and no backup is made.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { name: String }
+    /// # impl tomlconf::ConfigData for Config {
+    /// #     const DEFAULT: &'static str = "name = 'default'";
+    /// # }
+    /// use tomlconf::ConfigData;
+    ///
+    /// let dir = std::env::temp_dir().join("tomlconf-create-if-absent");
+    /// let path = dir.join("config.toml");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    ///
+    /// assert!(Config::create_if_absent(&path).unwrap());
+    ///
+    /// std::fs::write(&path, "name = 'custom'").unwrap();
+    /// assert!(!Config::create_if_absent(&path).unwrap());
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "name = 'custom'");
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    fn create_if_absent(path: &Path) -> Result<bool, std::io::Error> {
+        prepare_write(path, &SaveOptions::new(false, true))?;
+
+        let file = OpenOptions::new().write(true).create_new(true).open(path);
+
+        match file {
+            Ok(mut file) => {
+                file.write_all(&encode(path, &Self::default_content())?)?;
+                Ok(true)
             }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e),
         }
+    }
 
-        File::create(path)?.write_all(Self::DEFAULT.as_bytes())
+    /// Return the text to be written when creating a new default file. This is
+    ///     [`DEFAULT`] itself, unless overridden.
+    ///
+    /// Override this in order to fill in values which cannot be known at
+    ///     compile time, such as a generated identifier or the name of the
+    ///     current user. The result must still be valid in the format selected
+    ///     by `F`, and should describe the same structure as [`DEFAULT`].
+    ///
+    /// [`DEFAULT`]: Self::DEFAULT
+    fn default_content() -> Cow<'static, str> {
+        Cow::Borrowed(Self::DEFAULT)
     }
 
     /// Load the default configuration directly, without looking for a file.
-    fn default() -> Result<Self, toml::de::Error> {
-        let new: Self = toml::from_str(Self::DEFAULT)?;
+    fn default() -> Result<Self, F::DeError> {
+        let new: Self = F::from_str(Self::DEFAULT)?;
 
         Ok(new.prepare())
     }
 
-    /// Find and read a configuration file from a path defined programmatically
+    /// Load the default configuration directly, as with [`default`], but only
+    ///     parse it the first time this is called for a given type. Later calls
+    ///     return a clone of the cached value.
+    ///
+    /// [`default`]: Self::default
+    fn default_cached() -> Result<Self, F::DeError> where
+        Self: Clone + Send + Sync + 'static,
+        F: 'static,
+    {
+        crate::cache::cached::<(Self, F), _, _>(Self::default)
+    }
+
+    /// Load the default configuration directly, as with [`default`], and pair
+    ///     it with a path at which it may later be saved. Nothing is written.
+    ///
+    /// [`default`]: Self::default
+    fn default_at(path: PathBuf) -> Result<ConfigFile<Self>, F::DeError> {
+        Ok(Self::default()?.with_path(path))
+    }
+
+    /// Produce a reference of every key in the configuration, as TOML in which
+    ///     each line is commented out, holding the value of the default
+    ///     configuration, as parsed by [`default`].
+    ///
+    /// This is intended for generating documentation, or a template file which
+    ///     users may uncomment selectively. Since it is built from the type
+    ///     itself, it cannot drift out of date. Fields which are [`None`] by
+    ///     default are left out, since TOML cannot represent them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use tomlconf::ConfigData;
+    ///
+    /// #[derive(Deserialize, Serialize)]
+    /// struct Config { name: String, retries: Option<u32> }
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = r#"name = "app""#;
+    /// }
+    ///
+    /// assert_eq!(Config::schema_toml().unwrap(), "# name = \"app\"\n");
+    /// ```
+    ///
+    /// [`default`]: Self::default
+    fn schema_toml() -> Result<String, FormatError>
+        where Self: Serialize
+    {
+        let text = toml::to_string(&Self::default()?)?;
+        let mut out = String::with_capacity(text.len() * 2);
+
+        for line in text.lines() {
+            match line.is_empty() {
+                true => out.push('\n'),
+                false => {
+                    out.push_str("# ");
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Panic unless the default configuration can be parsed, prepared, and
+    ///     accepted by [`validate`]. If [`default_content`] is overridden, the
+    ///     text it produces is checked as well.
+    ///
+    /// This is intended to be called from a unit test, so that a broken default
+    ///     is found before it is shipped, rather than on the first launch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+    /// use tomlconf::ConfigData;
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 3";
+    /// }
+    ///
+    /// //  Typically within a `#[test]` function.
+    /// Config::assert_default_valid();
+    /// ```
+    ///
+    /// [`default_content`]: Self::default_content
+    /// [`validate`]: Self::validate
+    fn assert_default_valid() {
+        let check = |name: &str, text: &str| {
+            let config = match Self::from_text(text.to_owned()) {
+                Ok(config) => config,
+                Err(e) => panic!("{} cannot be parsed: {}", name, e),
+            };
+
+            if let Err(msg) = config.validate() {
+                panic!("{} is not valid: {}", name, msg);
+            }
+        };
+
+        check("Default configuration", Self::DEFAULT);
+
+        if let Cow::Owned(text) = Self::default_content() {
+            check("Default file content", &text);
+        }
+    }
+
+    /// Find and read a configuration file from a path defined programmatically
     ///     by [`ProjectDirs`].
     ///
     /// # Arguments
@@ -271,262 +1167,2187 @@ pub trait ConfigData: DeserializeOwned {
     /// returns: `ConfigFind<Self>`
     ///
     /// [`config_dir`]: ProjectDirs::config_dir
+    #[cfg(feature = "directories")]
     fn find(
         qualifier: &str,
         organization: &str,
         application: &str,
         file: &str,
     ) -> ConfigFind<Self> {
-        match find_path(qualifier, organization, application, file) {
-            None => ConfigFind::NoPath,
-            Some(path) if !path.exists() => ConfigFind::DoesNotExist(path),
-            Some(path) => {
-                let open = Self::open(&path);
-                ConfigFind::Exists(path, open)
-            }
-        }
+        Self::find_with_kind(
+            DirKind::Config,
+            qualifier, organization, application, file,
+        )
     }
 
-    /// Read a new configuration from a specific file, if it exists.
-    fn from_path(path: PathBuf) -> ConfigFind<Self> {
-        if path.exists() {
-            let open = Self::open(&path);
-            ConfigFind::Exists(path, open)
-        } else {
-            ConfigFind::DoesNotExist(path)
-        }
+    /// Find and read a configuration file from a path defined programmatically
+    ///     by [`ProjectDirs`], as with [`find`], taking each name as a distinct
+    ///     type so that they cannot be confused with one another.
+    ///
+    /// [`find`]: Self::find
+    #[cfg(feature = "directories")]
+    fn find_for(
+        qualifier: Qualifier,
+        organization: Organization,
+        application: Application,
+        file: FileName,
+    ) -> ConfigFind<Self> {
+        Self::find(qualifier.0, organization.0, application.0, file.0)
     }
 
-    /// Read a new configuration from a specific filepath, without first
-    ///     confirming that the file exists.
-    fn open(path: &Path) -> ConfigOpen<Self> {
-        use ConfigOpen::*;
+    /// Find and read a configuration file from a path defined programmatically
+    ///     by [`ProjectDirs`], in a specific kind of directory.
+    ///
+    /// Arguments following `kind` are the same as those of [`find`].
+    ///
+    /// [`find`]: Self::find
+    #[cfg(feature = "directories")]
+    fn find_with_kind(
+        kind: DirKind,
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+    ) -> ConfigFind<Self> {
+        Self::find_by(InProjectDirs {
+            kind, qualifier, organization, application, file,
+        })
+    }
 
-        let data = match File::open(&path) {
-            Ok(mut file) => match file.seek(SeekFrom::End(0)) {
-                Ok(len) => match file.rewind() {
-                    Ok(..) => {
-                        let mut buf = String::with_capacity(1 + len as usize);
-
-                        match file.read_to_string(&mut buf) {
-                            Ok(..) => buf,
-                            Err(e) => { return FileInaccessible(e); }
-                        }
-                    }
-                    Err(e) => { return FileInaccessible(e); }
-                }
-                Err(e) => { return FileInaccessible(e); }
+    /// Find and read a configuration file at the path given by a
+    ///     [`PathStrategy`].
+    #[cfg(feature = "fs")]
+    fn find_by(strategy: impl PathStrategy) -> ConfigFind<Self> {
+        match strategy.config_path() {
+            None => ConfigFind::NoPath,
+            Some(path) if !path.exists() => ConfigFind::DoesNotExist(path),
+            Some(path) => {
+                let open = Self::open(&path);
+                ConfigFind::Exists(path, open)
             }
-            Err(e) => { return FileInaccessible(e); }
-        };
-
-        match toml::from_str::<Self>(&data) {
-            Err(e) => FileInvalid(e),
-            Ok(config) => FileValid(config.prepare()),
         }
     }
 
-    /// Perform any final transformations that should be done on a newly parsed
-    ///     configuration, such as enforcing specific casing for string fields.
-    ///
-    /// This is a no-op by default, and is intended to be overridden.
-    fn prepare(self) -> Self { self }
-
-    /// Associate a file path with this configuration.
-    fn with_path(self, path: PathBuf) -> ConfigFile<Self> {
-        ConfigFile { data: self, path }
-    }
-
-    /// Attempt to automatically handle all aspects of configuration setup.
+    /// List every path at which the finders of this trait may look for a
+    ///     configuration file, in the order of the kinds of [`DirKind`], each
+    ///     paired with whether a file currently exists there.
     ///
-    /// If any step of the process fails, this function returns a description
-    ///     stating plainly what went wrong, suitable for immediate delivery to
-    ///     the user. If the process is successful, the return is a string
-    ///     describing the source of the configuration (either opening a found
-    ///     file or creating a new one), as well as the configuration structure
-    ///     itself.
+    /// Paths which are the same for several kinds of directory are only listed
+    ///     once. This is intended for diagnostics, to help users find out which
+    ///     file is being used.
     ///
     /// Arguments passed to this function are the same as those of [`find`].
     ///
     /// [`find`]: Self::find
-    fn setup(
+    #[cfg(feature = "directories")]
+    fn candidate_paths(
         qualifier: &str,
         organization: &str,
         application: &str,
         file: &str,
-    ) -> Result<(String, ConfigFile<Self>), String> {
-        use ConfigFind::*;
+    ) -> Vec<(PathBuf, bool)> {
+        let mut paths: Vec<(PathBuf, bool)> = Vec::new();
 
-        match Self::find(qualifier, organization, application, file) {
-            DoesNotExist(path) => match Self::create(&path, true, true) {
-                Err(e) => Err(format!(
-                    "Cannot save {} as Config file: {}",
-                    path.display(), e,
-                )),
-                Ok(..) => match Self::open(&path) {
-                    ConfigOpen::FileInaccessible(e) => Err(format!(
-                        "Cannot access {} as Config file: {}",
-                        path.display(), e,
-                    )),
-                    ConfigOpen::FileInvalid(e) => Err(format!(
-                        "Cannot read {} as Config file: {}",
-                        path.display(), e,
-                    )),
-                    ConfigOpen::FileValid(cfg) => Ok((
-                        format!("Created new Config file: {}", path.display()),
-                        cfg.with_path(path),
-                    )),
+        for kind in [DirKind::Config, DirKind::ConfigLocal] {
+            let path = find_path(kind, qualifier, organization, application, file);
+
+            if let Some(path) = path {
+                if paths.iter().all(|(seen, _)| *seen != path) {
+                    let exists = path.exists();
+                    paths.push((path, exists));
                 }
             }
-            Exists(path, cfg) => match cfg {
-                ConfigOpen::FileInaccessible(e) => Err(format!(
-                    "Cannot access {} as Config file: {}",
-                    path.display(), e,
-                )),
-                ConfigOpen::FileInvalid(e) => Err(format!(
-                    "Cannot read {} as Config file: {}",
-                    path.display(), e,
-                )),
-                ConfigOpen::FileValid(cfg) => Ok((
-                    format!("Using existing Config file: {}", path.display()),
-                    cfg.with_path(path),
-                )),
-            }
-            NoPath => Err(String::from("Cannot find path for Config file.")),
         }
+
+        paths
     }
 
-    /// Attempt to automatically handle all aspects of configuration setup.
-    ///
-    /// If the target file exists, but is invalid, it will be overwritten with a
-    ///     new default file.
+    /// Find and read a configuration file from a path defined programmatically
+    ///     by [`ProjectDirs`], as with [`find`], but report a failure to check
+    ///     whether the file exists, rather than assuming that it does not.
     ///
     /// Arguments passed to this function are the same as those of [`find`].
     ///
     /// [`find`]: Self::find
-    fn setup_replace_invalid(
+    #[cfg(feature = "directories")]
+    fn try_find(
         qualifier: &str,
         organization: &str,
         application: &str,
         file: &str,
-    ) -> Result<(String, ConfigFile<Self>), String> {
-        use ConfigFind::*;
+    ) -> ConfigFind<Self> {
+        let path = find_path(
+            DirKind::Config,
+            qualifier, organization, application, file,
+        );
 
-        match Self::find(qualifier, organization, application, file) {
-            Exists(path, ConfigOpen::FileInvalid(_))
-            | DoesNotExist(path) => match Self::create(&path, true, true) {
-                Err(e) => Err(format!(
-                    "Cannot save {} as Config file: {}",
-                    path.display(), e,
-                )),
-                Ok(..) => match Self::open(&path) {
-                    ConfigOpen::FileInaccessible(e) => Err(format!(
-                        "Cannot access {} as Config file: {}",
-                        path.display(), e,
-                    )),
-                    ConfigOpen::FileInvalid(e) => Err(format!(
-                        "Cannot read {} as Config file: {}",
-                        path.display(), e,
-                    )),
-                    ConfigOpen::FileValid(cfg) => Ok((
-                        format!("Created new Config file: {}", path.display()),
-                        cfg.with_path(path),
-                    )),
-                }
+        match path {
+            None => ConfigFind::NoPath,
+            Some(path) => Self::try_from_path(path),
+        }
+    }
+
+    /// Read a new configuration from a specific file, if it exists.
+    #[cfg(feature = "fs")]
+    fn from_path(path: PathBuf) -> ConfigFind<Self> {
+        if path.exists() {
+            let open = Self::open(&path);
+            ConfigFind::Exists(path, open)
+        } else {
+            ConfigFind::DoesNotExist(path)
+        }
+    }
+
+    /// Look for a configuration file at a specific path, returning an entry
+    ///     which may be completed into a [`ConfigFile`], whether or not the
+    ///     file exists, in the manner of [`HashMap::entry`].
+    ///
+    /// If the file exists, it is opened immediately. If it cannot be determined
+    ///     whether it exists, the entry is [`Occupied`], holding the error, so
+    ///     that a file which may exist is never replaced.
+    ///
+    /// [`HashMap::entry`]: std::collections::HashMap::entry
+    /// [`Occupied`]: ConfigEntry::Occupied
+    #[cfg(feature = "fs")]
+    fn entry(path: impl Into<PathBuf>) -> ConfigEntry<Self> {
+        let path = path.into();
+
+        match path.try_exists() {
+            Ok(true) => {
+                let open = Self::open(&path);
+                ConfigEntry::Occupied(path, open)
             }
-            Exists(path, cfg) => match cfg {
-                ConfigOpen::FileInaccessible(e) => Err(format!(
-                    "Cannot access {} as Config file: {}",
-                    path.display(), e,
-                )),
-                ConfigOpen::FileInvalid(_) => unreachable!(),
-                ConfigOpen::FileValid(cfg) => Ok((
-                    format!("Using existing Config file: {}", path.display()),
-                    cfg.with_path(path),
-                )),
+            Ok(false) => ConfigEntry::Vacant(path),
+            Err(e) => {
+                ConfigEntry::Occupied(path, ConfigOpen::FileInaccessible(e))
             }
-            NoPath => Err(String::from("Cannot find path for Config file.")),
         }
     }
-}
 
+    /// Read a new configuration from a specific file, if it exists, as with
+    ///     [`from_path`], but return [`ConfigFind::Inaccessible`] if it cannot
+    ///     be determined whether the file exists.
+    ///
+    /// [`from_path`]: Self::from_path
+    #[cfg(feature = "fs")]
+    fn try_from_path(path: PathBuf) -> ConfigFind<Self> {
+        match path.try_exists() {
+            Err(e) => ConfigFind::Inaccessible(path, e),
+            Ok(false) => ConfigFind::DoesNotExist(path),
+            Ok(true) => {
+                let open = Self::open(&path);
+                ConfigFind::Exists(path, open)
+            }
+        }
+    }
 
-/// A pairing of a configuration state with the file path at which it is saved.
-#[derive(Clone)]
-pub struct ConfigFile<Cfg> {
-    /// The stored configuration state.
-    pub data: Cfg,
-    /// The path to the file associated with this configuration state.
-    pub path: PathBuf,
-}
-
+    /// Read a new configuration from a specific file, creating it with the
+    ///     default configuration first if it does not exist.
+    ///
+    /// The parent directory of a new file is created if necessary. An existing
+    ///     file which cannot be parsed is left untouched, and reported as an
+    ///     error.
+    #[cfg(feature = "fs")]
+    fn open_or_create(path: PathBuf) -> Result<ConfigFile<Self>, ConfigError> {
+        if !path.try_exists()? {
+            Self::create(&path, false, true)?;
+        }
 
-impl<Cfg: ConfigData> ConfigFile<Cfg> {
-    /// Read from the file associated with this configuration, and replace the
-    ///     stored data.
-    #[cfg(not(feature = "nightly"))]
-    pub fn reload(&mut self) -> Result<(), ConfigOpen<Cfg>> {
-        // use std::mem::replace;
+        let data = Self::open(&path).into_config_result()?;
+        Ok(data.with_path(path))
+    }
 
-        match Cfg::open(&self.path) {
-            // ConfigOpen::FileValid(new) => Ok(replace(&mut self.data, new)),
-            ConfigOpen::FileValid(new) => {
-                self.data = new;
-                Ok(())
-            }
-            err => Err(err),
+    /// Read a new configuration from a specific filepath, prepare it, and check
+    ///     it with [`validate`], returning it ready for use.
+    ///
+    /// This is the simplest way to read a configuration from a known path, for
+    ///     programs which only need to report why it failed. Use [`open`] to
+    ///     distinguish between the kinds of failure while reading the file.
+    ///
+    /// [`open`]: Self::open
+    /// [`validate`]: Self::validate
+    #[cfg(feature = "fs")]
+    fn load(path: &Path) -> Result<Self, ConfigError> {
+        let config = Self::open(path).into_config_result()?;
+
+        match config.validate() {
+            Ok(()) => Ok(config),
+            Err(msg) => Err(ConfigError::ValidationFailed(msg)),
         }
     }
 
-    /// Read from the file associated with this configuration, and replace the
-    ///     stored data.
-    #[cfg(feature = "nightly")]
-    pub fn reload(&mut self) -> Result<(), ConfigOpen<Cfg>> {
-        self.data = Cfg::open(&self.path)?;
-        Ok(())
-
-        // Ok(std::mem::replace(&mut self.data, Cfg::open(&self.path)?))
+    /// Read a new configuration from a specific filepath, without first
+    ///     confirming that the file exists.
+    ///
+    /// The new configuration is passed through [`prepare`] before it is
+    ///     returned. To skip this step, use [`open_raw`] instead.
+    ///
+    /// [`open_raw`]: Self::open_raw
+    /// [`prepare`]: Self::prepare
+    #[cfg(feature = "fs")]
+    fn open(path: &Path) -> ConfigOpen<Self> {
+        Self::open_storage(path)
     }
 
-    /// Write the configuration into a new file at the associated path.
-    pub fn save(
-        &self,
-        create_backup: bool,
-        create_parent: bool,
-    ) -> Result<(), ConfigSaveError>
-        where Cfg: Serialize
-    {
-        let Self { data, path } = self;
+    /// Read a new configuration from a specific filepath, as with [`open`], or
+    ///     from standard input if the path is exactly `-`, following the usual
+    ///     convention of command line programs.
+    ///
+    /// Standard input is read in full before it is parsed. Since there is no
+    ///     file, the configuration is prepared by [`prepare`] rather than by
+    ///     [`prepare_with_path`]. A file which is actually named `-` may still
+    ///     be opened as `./-`, or by [`open`].
+    ///
+    /// [`open`]: Self::open
+    /// [`prepare`]: Self::prepare
+    /// [`prepare_with_path`]: Self::prepare_with_path
+    #[cfg(feature = "fs")]
+    fn open_stdin_or_path(path: &Path) -> ConfigOpen<Self> {
+        use std::io::Read;
+
+        if path.as_os_str() != "-" {
+            return Self::open(path);
+        }
 
-        if create_backup && path.exists() {
-            if let Some(backup) = get_backup(path) {
-                rename(path, backup).ok();
-            }
-        } else if create_parent {
-            if let Some(parent) = path.parent() {
-                if !parent.exists() {
-                    create_dir_all(parent)?;
-                }
-            }
+        let mut text = String::new();
+
+        match std::io::stdin().read_to_string(&mut text) {
+            Ok(_) => Self::from_text_env(text, None),
+            Err(e) => ConfigOpen::FileInaccessible(e),
         }
+    }
 
-        let serial: String = toml::to_string(data)?;
-        Ok(File::create(path)?.write_all(serial.as_bytes())?)
+    /// Read a new configuration from a specific filepath, as with [`open`], but
+    ///     without passing it through [`prepare`].
+    ///
+    /// This may be used to compare the configuration as it was written by the
+    ///     user with the prepared form.
+    ///
+    /// [`open`]: Self::open
+    /// [`prepare`]: Self::prepare
+    #[cfg(feature = "fs")]
+    fn open_raw(path: &Path) -> ConfigOpen<Self> {
+        match path.read() {
+            Err(e) => ConfigOpen::FileInaccessible(e),
+            Ok(data) => match fill_empty::<Self, F>(data) {
+                None => ConfigOpen::FileEmpty,
+                Some(data) => match Self::from_text_raw(data) {
+                    Err(e) => ConfigOpen::FileInvalid(e),
+                    Ok(config) => ConfigOpen::FileValid(config),
+                },
+            },
+        }
     }
-}
 
+    /// Read a new configuration from a specific filepath, as with [`open`], and
+    ///     verify it against the checksum file saved alongside it.
+    ///
+    /// If there is no checksum file, the configuration is read normally. A
+    ///     checksum file is written when a configuration is saved with the
+    ///     [`write_checksum`] option. To skip verification, for instance after
+    ///     the file has been edited by hand, use [`open`] instead.
+    ///
+    /// [`open`]: Self::open
+    /// [`write_checksum`]: SaveOptions::write_checksum
+    #[cfg(feature = "checksum")]
+    fn open_verified(path: &Path) -> ConfigOpen<Self> {
+        use ConfigOpen::*;
 
-impl<Cfg> Deref for ConfigFile<Cfg> {
-    type Target = Cfg;
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => { return FileInaccessible(e); }
+        };
 
-    fn deref(&self) -> &Self::Target {
-        &self.data
+        match crate::checksum::verify(path, &bytes) {
+            Ok(true) => {}
+            Ok(false) => { return ChecksumMismatch; }
+            Err(e) => { return FileInaccessible(e); }
+        }
+
+        match crate::storage::decode(path, bytes) {
+            Ok(data) => Self::from_text_env(data, Some(path)),
+            Err(e) => FileInaccessible(e),
+        }
     }
-}
 
+    /// Read a new configuration from a specific filepath, as with [`open`], but
+    ///     fail with [`ConfigOpen::UnknownKeys`] if the file contains any keys
+    ///     which the configuration does not declare.
+    ///
+    /// This has the same effect as `#[serde(deny_unknown_fields)]`, without
+    ///     requiring control of the definition of the type, and reports every
+    ///     unknown key at once. It is useful for checking that a file matches
+    ///     the current version of the configuration.
+    ///
+    /// [`open`]: Self::open
+    #[cfg(feature = "fs")]
+    fn open_strict(path: &Path) -> ConfigOpen<Self> {
+        use ConfigOpen::*;
 
-impl<Cfg> DerefMut for ConfigFile<Cfg> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.data
+        let text = match path.read().map(fill_empty::<Self, F>) {
+            Ok(Some(text)) => text,
+            Ok(None) => { return FileEmpty; }
+            Err(e) => { return FileInaccessible(e); }
+        };
+
+        match F::from_str_tracked::<Self>(&text) {
+            Err(e) => FileInvalid(ParseError::new::<F>(e, text)),
+            Ok((_, keys)) if !keys.is_empty() => UnknownKeys(keys),
+            Ok((config, _)) => match config.apply_env() {
+                Err(e) => EnvInvalid(e),
+                Ok(config) => FileValid(config.prepare_with_path(path)),
+            },
+        }
+    }
+
+    /// Read a new configuration from a specific filepath, as with [`open`], and
+    ///     also return a list of [`Warning`]s about problems which did not
+    ///     prevent it from being used.
+    ///
+    /// Keys which the configuration does not declare are reported as
+    ///     [`Warning::UnknownKey`], rather than being passed to
+    ///     [`unknown_keys`]. The configuration is then prepared by
+    ///     [`prepare_with_warnings`], which may add warnings of its own. The
+    ///     list is returned even if the file could not be opened, holding any
+    ///     warnings found before the failure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { level: u32 }
+    /// use std::path::Path;
+    /// use tomlconf::{ConfigData, Warning};
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "level = 5";
+    ///
+    ///     fn prepare_with_warnings(
+    ///         mut self,
+    ///         path: &Path,
+    ///         warnings: &mut Vec<Warning>,
+    ///     ) -> Self {
+    ///         if self.level > 10 {
+    ///             warnings.push(Warning::Other(format!(
+    ///                 "level {} is too high, using 10", self.level,
+    ///             )));
+    ///             self.level = 10;
+    ///         }
+    ///
+    ///         self.prepare_with_path(path)
+    ///     }
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join("tomlconf-open-warnings.toml");
+    /// std::fs::write(&path, "level = 50\nlevle = 3").unwrap();
+    ///
+    /// let (open, warnings) = Config::open_with_warnings(&path);
+    /// assert_eq!(open.config().unwrap().level, 10);
+    /// assert_eq!(warnings, [
+    ///     Warning::UnknownKey("levle".into()),
+    ///     Warning::Other("level 50 is too high, using 10".into()),
+    /// ]);
+    /// # std::fs::remove_file(&path).ok();
+    /// ```
+    ///
+    /// [`open`]: Self::open
+    /// [`prepare_with_warnings`]: Self::prepare_with_warnings
+    /// [`unknown_keys`]: Self::unknown_keys
+    #[cfg(feature = "fs")]
+    fn open_with_warnings(path: &Path) -> (ConfigOpen<Self>, Vec<Warning>) {
+        use ConfigOpen::*;
+
+        let text = match path.read().map(fill_empty::<Self, F>) {
+            Ok(Some(text)) => text,
+            Ok(None) => { return (FileEmpty, Vec::new()); }
+            Err(e) => { return (FileInaccessible(e), Vec::new()); }
+        };
+
+        let (config, keys) = match F::from_str_tracked::<Self>(&text) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return (FileInvalid(ParseError::new::<F>(e, text)), Vec::new());
+            }
+        };
+
+        let mut warnings: Vec<Warning> = keys.into_iter()
+            .map(Warning::UnknownKey)
+            .collect();
+
+        let open = match config.apply_env() {
+            Err(e) => EnvInvalid(e),
+            Ok(config) => {
+                FileValid(config.prepare_with_warnings(path, &mut warnings))
+            }
+        };
+
+        (open, warnings)
+    }
+
+    /// Read a new configuration from a specific filepath, as with [`open`], and
+    ///     also read any other files which it includes.
+    ///
+    /// A file may include others by listing their paths under the top-level
+    ///     key `include`, either as a single string or as an array of strings.
+    ///     Relative paths are taken to be relative to the directory containing
+    ///     the file which includes them. Included files may include further
+    ///     files, but a file may not include itself, directly or indirectly.
+    ///
+    /// Included files are merged in the order they are listed, in the same way
+    ///     as the layers of a [`LayeredConfig`], and the including file is then
+    ///     merged over them, so that its own values take precedence. If any
+    ///     file cannot be read or parsed, the error describes the chain of
+    ///     files which included it.
+    ///
+    /// This is only available for configurations stored as [`Toml`]. Since it
+    ///     gives special meaning to the `include` key, [`open`] does not do
+    ///     this on its own.
+    ///
+    /// [`LayeredConfig`]: crate::LayeredConfig
+    /// [`open`]: Self::open
+    #[cfg(feature = "fs")]
+    fn open_with_includes(path: &Path) -> ConfigOpen<Self>
+        where Self: ConfigData<Toml>
+    {
+        crate::include::open(path)
+    }
+
+    /// Read a new configuration from a specific filepath, as with [`open`], but
+    ///     deserialize it through a [`DeserializeSeed`], so that a value known
+    ///     only at runtime, such as a registry of names which the file refers
+    ///     to, may be used while the text is parsed.
+    ///
+    /// The seed must produce the configuration itself, and is usually written
+    ///     by hand, deserializing part of the data as usual and then resolving
+    ///     the rest with its own state. An error returned by the seed, such as
+    ///     one made by [`Error::custom`], is reported as
+    ///     [`ConfigOpen::FileInvalid`] like any other parse error. The result
+    ///     then passes through [`apply_env`] and [`prepare_with_path`] as
+    ///     usual, but since the type is not deserialized directly, keys which
+    ///     the configuration does not declare are not reported to
+    ///     [`unknown_keys`].
+    ///
+    /// This is only available for configurations stored as [`Toml`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use serde::{de::{DeserializeSeed, Error}, Deserialize, Deserializer};
+    /// use tomlconf::ConfigData;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config { color: String }
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = r#"color = "red""#;
+    /// }
+    ///
+    /// /// Accepts only colors which are known to the application.
+    /// struct Palette<'a>(&'a HashMap<&'static str, u32>);
+    ///
+    /// impl<'de> DeserializeSeed<'de> for Palette<'_> {
+    ///     type Value = Config;
+    ///
+    ///     fn deserialize<D>(self, de: D) -> Result<Config, D::Error>
+    ///         where D: Deserializer<'de>
+    ///     {
+    ///         let config = Config::deserialize(de)?;
+    ///
+    ///         match self.0.contains_key(config.color.as_str()) {
+    ///             true => Ok(config),
+    ///             false => Err(D::Error::custom("unknown color")),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let palette = HashMap::from([("red", 0xff0000)]);
+    /// let path = std::env::temp_dir().join("tomlconf-open-seeded.toml");
+    /// Config::create(&path, false, true).unwrap();
+    ///
+    /// let open = Config::open_seeded(&path, Palette(&palette));
+    /// assert_eq!(open.config().unwrap().color, "red");
+    /// # std::fs::remove_file(&path).ok();
+    /// ```
+    ///
+    /// [`apply_env`]: Self::apply_env
+    /// [`DeserializeSeed`]: serde::de::DeserializeSeed
+    /// [`Error::custom`]: serde::de::Error::custom
+    /// [`open`]: Self::open
+    /// [`prepare_with_path`]: Self::prepare_with_path
+    /// [`unknown_keys`]: Self::unknown_keys
+    #[cfg(feature = "fs")]
+    fn open_seeded<S>(path: &Path, seed: S) -> ConfigOpen<Self> where
+        Self: ConfigData<Toml>,
+        S: for<'de> serde::de::DeserializeSeed<'de, Value = Self>,
+    {
+        use ConfigOpen::*;
+
+        let text = match path.read().map(fill_empty::<Self, Toml>) {
+            Ok(Some(text)) => text,
+            Ok(None) => { return FileEmpty; }
+            Err(e) => { return FileInaccessible(e); }
+        };
+
+        let parsed = seed.deserialize(&mut toml::Deserializer::new(&text));
+
+        match parsed.map(<Self as ConfigData<Toml>>::apply_env) {
+            Err(e) => FileInvalid(ParseError::new::<Toml>(e, text)),
+            Ok(Err(e)) => EnvInvalid(e),
+            Ok(Ok(config)) => FileValid(
+                <Self as ConfigData<Toml>>::prepare_with_path(config, path),
+            ),
+        }
+    }
+
+    /// Read a new configuration from a specific filepath, as with [`open`], and
+    ///     also return the parsed text as a generic [`toml::Value`].
+    ///
+    /// The value holds every key in the file, including those which the
+    ///     configuration does not declare, so that other parts of a program,
+    ///     such as plugins, may read their own sections from it. It is returned
+    ///     whenever the text is valid TOML, even if it does not describe a
+    ///     valid configuration.
+    ///
+    /// This is only available for configurations stored as [`Toml`].
+    ///
+    /// [`open`]: Self::open
+    #[cfg(feature = "fs")]
+    fn open_with_value(path: &Path) -> (ConfigOpen<Self>, Option<toml::Value>)
+        where Self: ConfigData<Toml>
+    {
+        let text = match path.read().map(fill_empty::<Self, Toml>) {
+            Ok(Some(text)) => text,
+            Ok(None) => { return (ConfigOpen::FileEmpty, None); }
+            Err(e) => { return (ConfigOpen::FileInaccessible(e), None); }
+        };
+
+        let value = toml::from_str(&text).ok();
+        (<Self as ConfigData<Toml>>::from_text_env(text, Some(path)), value)
+    }
+
+    /// Read a new configuration from a directory of fragments, such as a
+    ///     `conf.d` directory, in which every file ending in `.toml` is a part
+    ///     of the configuration. Other files are ignored.
+    ///
+    /// The fragments are read in order of their names, and merged in the same
+    ///     way as the layers of a [`LayeredConfig`]. If a fragment cannot be
+    ///     parsed, its path is attached to the resulting [`ParseError`].
+    ///
+    /// This is only available for configurations stored as [`Toml`].
+    ///
+    /// [`LayeredConfig`]: crate::LayeredConfig
+    #[cfg(feature = "fs")]
+    fn load_dir(dir: &Path) -> ConfigOpen<Self> where Self: ConfigData<Toml> {
+        crate::layered::load_dir(dir)
+    }
+
+    /// Read a separate configuration from every file matching a glob pattern,
+    ///     such as `plugins/*.toml`, returning the path of each file together
+    ///     with the result of opening it.
+    ///
+    /// Unlike [`load_dir`], the files are not merged; Each is opened on its
+    ///     own, as with [`open`], so that one invalid file does not prevent the
+    ///     others from being used. The results are sorted by path. Directories
+    ///     matching the pattern are skipped, and a file which cannot be reached
+    ///     while searching is reported as [`ConfigOpen::FileInaccessible`].
+    ///
+    /// A pattern which is not valid matches no files at all.
+    ///
+    /// [`load_dir`]: Self::load_dir
+    /// [`open`]: Self::open
+    #[cfg(feature = "glob")]
+    fn open_glob(pattern: &str) -> Vec<(PathBuf, ConfigOpen<Self>)> {
+        let paths = match glob::glob(pattern) {
+            Ok(paths) => paths,
+            Err(..) => return Vec::new(),
+        };
+
+        let mut found: Vec<(PathBuf, ConfigOpen<Self>)> = paths
+            .filter_map(|entry| match entry {
+                Ok(path) if path.is_dir() => None,
+                Ok(path) => {
+                    let open = Self::open(&path);
+                    Some((path, open))
+                }
+                Err(e) => {
+                    let path = e.path().to_owned();
+                    Some((path, ConfigOpen::FileInaccessible(e.into())))
+                }
+            })
+            .collect();
+
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+    }
+
+    /// Read a new configuration from the full text held in an environment
+    ///     variable, such as one set by a container, rather than from a file.
+    ///
+    /// The text is parsed in the same way as the contents of a file, including
+    ///     the bindings declared by [`env_bindings`]. If the variable is not
+    ///     set, [`None`] is returned, so that the caller may fall back to
+    ///     reading a file instead. A value which is not valid Unicode is
+    ///     reported as [`ConfigOpen::FileInaccessible`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+    /// use tomlconf::ConfigData;
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 3";
+    /// }
+    ///
+    /// std::env::set_var("MYAPP_CONFIG_TOML", "number = 5");
+    /// let open = Config::from_env_var("MYAPP_CONFIG_TOML").unwrap();
+    /// assert_eq!(open.config().unwrap().number, 5);
+    ///
+    /// assert!(Config::from_env_var("MYAPP_NOT_SET").is_none());
+    /// ```
+    ///
+    /// [`env_bindings`]: Self::env_bindings
+    fn from_env_var(var: &str) -> Option<ConfigOpen<Self>> {
+        use std::{env::{var as get, VarError}, io::{Error, ErrorKind}};
+
+        match get(var) {
+            Ok(text) => Some(Self::from_text_env(text, None)),
+            Err(VarError::NotPresent) => None,
+            Err(e @ VarError::NotUnicode(..)) => {
+                Some(ConfigOpen::FileInaccessible(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: {}", var, e),
+                )))
+            }
+        }
+    }
+
+    /// Read a new configuration from any kind of [`ConfigStorage`].
+    fn open_storage<S: ConfigStorage + ?Sized>(storage: &S) -> ConfigOpen<Self> {
+        use ConfigOpen::*;
+
+        let data = match storage.read() {
+            Ok(data) => data,
+            Err(e) => { return FileInaccessible(e); }
+        };
+
+        Self::from_text_env(data, storage.path())
+    }
+
+    /// Parse a new configuration from text, apply the bindings declared by
+    ///     [`env_bindings`], and prepare it for use.
+    ///
+    /// If the path of the file from which the text was read is given, the
+    ///     configuration is prepared by [`prepare_with_path`], and otherwise by
+    ///     [`prepare`].
+    ///
+    /// [`env_bindings`]: Self::env_bindings
+    /// [`prepare`]: Self::prepare
+    /// [`prepare_with_path`]: Self::prepare_with_path
+    fn from_text_env(text: String, path: Option<&Path>) -> ConfigOpen<Self> {
+        use ConfigOpen::*;
+
+        let text = match fill_empty::<Self, F>(text) {
+            Some(text) => text,
+            None => { return FileEmpty; }
+        };
+
+        match Self::from_text_raw(text) {
+            Err(e) => FileInvalid(e),
+            Ok(config) => match config.apply_env() {
+                Err(e) => EnvInvalid(e),
+                Ok(config) => FileValid(match path {
+                    Some(path) => config.prepare_with_path(path),
+                    None => config.prepare(),
+                }),
+            },
+        }
+    }
+
+    /// Return the bindings between fields and environment variables which are
+    ///     applied when this configuration is opened. There are none, unless
+    ///     this is overridden.
+    fn env_bindings() -> Vec<EnvBinding<Self>> { Vec::new() }
+
+    /// Override fields of this configuration with the values of any bound
+    ///     environment variables which are set.
+    fn apply_env(mut self) -> Result<Self, EnvError> {
+        for binding in Self::env_bindings() {
+            binding.apply(&mut self)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Parse a new configuration from text, and prepare it for use.
+    ///
+    /// This does not touch the filesystem, and is available without the `fs`
+    ///     feature.
+    fn from_text(text: String) -> Result<Self, ParseError> {
+        Self::from_text_raw(text).map(Self::prepare)
+    }
+
+    /// Parse a new configuration from text, without passing it through
+    ///     [`prepare`].
+    ///
+    /// [`prepare`]: Self::prepare
+    fn from_text_raw(text: String) -> Result<Self, ParseError> {
+        match F::from_str_tracked::<Self>(&text) {
+            Err(e) => Err(ParseError::new::<F>(e, text)),
+            Ok((config, keys)) => {
+                if !keys.is_empty() {
+                    Self::unknown_keys(keys);
+                }
+
+                Ok(config)
+            }
+        }
+    }
+
+    /// Receive the path of each key which was found in the text of this
+    ///     configuration, but which it does not declare, such as a misspelled
+    ///     field name. This is called whenever such keys are found while
+    ///     parsing the text.
+    ///
+    /// Since these keys are otherwise ignored, this is a no-op by default, and
+    ///     is intended to be overridden in order to warn the user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+    /// use tomlconf::ConfigData;
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 5";
+    ///
+    ///     fn unknown_keys(keys: Vec<String>) {
+    ///         for key in keys {
+    ///             eprintln!("Warning: Unknown key in config: {}", key);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let config = Config::from_text("number = 5\nnumbre = 6".into()).unwrap();
+    /// assert_eq!(config.number, 5);
+    /// ```
+    fn unknown_keys(keys: Vec<String>) {
+        let _ = keys;
+    }
+
+    /// Convert this configuration into text.
+    ///
+    /// This does not touch the filesystem, and is available without the `fs`
+    ///     feature.
+    fn to_text(&self) -> Result<String, F::SerError> where Self: Serialize {
+        F::to_string(self)
+    }
+
+    /// Perform any final transformations that should be done on a newly parsed
+    ///     configuration, such as enforcing specific casing for string fields.
+    ///
+    /// By default, this calls [`prepare_mut`], and is intended to be overridden
+    ///     where it is more convenient to take the configuration by value.
+    ///
+    /// [`prepare_mut`]: Self::prepare_mut
+    fn prepare(mut self) -> Self {
+        self.prepare_mut();
+        self
+    }
+
+    /// Perform any final transformations that should be done on a newly parsed
+    ///     configuration, in place, as an alternative to [`prepare`] which does
+    ///     not move a large configuration.
+    ///
+    /// This is a no-op by default, and is intended to be overridden. It is only
+    ///     called by the default implementation of [`prepare`]; If [`prepare`]
+    ///     is also overridden, it takes precedence, and this is not called
+    ///     unless [`prepare`] calls it explicitly.
+    ///
+    /// [`prepare`]: Self::prepare
+    fn prepare_mut(&mut self) {}
+
+    /// Perform any final transformations that should be done on a configuration
+    ///     newly read from a file, with knowledge of the path of that file.
+    ///
+    /// This is called instead of [`prepare`] when a configuration is opened
+    ///     from a file, and by default simply calls [`prepare`]. If both are
+    ///     overridden, [`prepare`] is only called from here if this method
+    ///     calls it explicitly; It is still called alone when a configuration
+    ///     is parsed from text with no associated file, such as by
+    ///     [`default`] or [`from_text`].
+    ///
+    /// [`default`]: Self::default
+    /// [`from_text`]: Self::from_text
+    /// [`prepare`]: Self::prepare
+    fn prepare_with_path(self, path: &Path) -> Self {
+        let _ = path;
+        self.prepare()
+    }
+
+    /// Perform any final transformations that should be done on a configuration
+    ///     newly read from a file, as with [`prepare_with_path`], while also
+    ///     reporting any problems which do not prevent it from being used, such
+    ///     as a value which had to be clamped, by adding them to `warnings`.
+    ///
+    /// This is called instead of [`prepare_with_path`] by
+    ///     [`open_with_warnings`], and by default simply calls it, adding no
+    ///     warnings.
+    ///
+    /// [`open_with_warnings`]: Self::open_with_warnings
+    /// [`prepare_with_path`]: Self::prepare_with_path
+    fn prepare_with_warnings(
+        self,
+        path: &Path,
+        warnings: &mut Vec<Warning>,
+    ) -> Self {
+        let _ = warnings;
+        self.prepare_with_path(path)
+    }
+
+    /// Check that a prepared configuration makes sense as a whole, such as that
+    ///     a minimum is not greater than a maximum, and return a description
+    ///     of the problem if it does not.
+    ///
+    /// This is called by [`load`], after [`prepare`]. It accepts everything by
+    ///     default, and is intended to be overridden.
+    ///
+    /// [`load`]: Self::load
+    /// [`prepare`]: Self::prepare
+    fn validate(&self) -> Result<(), String> { Ok(()) }
+
+    /// Associate a file path with this configuration.
+    fn with_path(self, path: PathBuf) -> ConfigFile<Self> {
+        ConfigFile::new(self, path)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup.
+    ///
+    /// If any step of the process fails, this function returns a description
+    ///     stating plainly what went wrong, suitable for immediate delivery to
+    ///     the user. If the process is successful, the return is a string
+    ///     describing the source of the configuration (either opening a found
+    ///     file or creating a new one), as well as the configuration structure
+    ///     itself.
+    ///
+    /// Arguments passed to this function are the same as those of [`find`].
+    ///
+    /// [`find`]: Self::find
+    #[cfg(feature = "directories")]
+    fn setup(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_from(Self::find(qualifier, organization, application, file))
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, as
+    ///     with [`setup`], but fall back to other directories if no file can be
+    ///     found or created in the usual place, such as when the directory of
+    ///     the user is not writable.
+    ///
+    /// Each fallback directory is tried in order, as with [`setup_in`], and the
+    ///     first to succeed is used. A temporary location may be included by
+    ///     passing [`std::env::temp_dir`]. When a fallback is used, the message
+    ///     says so, and the returned [`ConfigFile`] holds its path. If every
+    ///     fallback fails, the error from the usual place is returned.
+    ///
+    /// A file which exists in the usual place, but cannot be read, is reported
+    ///     as an error without trying any fallback, so that a broken file is
+    ///     never silently replaced by another.
+    ///
+    /// [`setup`]: Self::setup
+    /// [`setup_in`]: Self::setup_in
+    #[cfg(feature = "directories")]
+    fn setup_with_fallback<P: AsRef<Path>>(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+        fallbacks: &[P],
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        let found = Self::find(qualifier, organization, application, file);
+        let missing = matches!(
+            found,
+            ConfigFind::DoesNotExist(..) | ConfigFind::NoPath,
+        );
+
+        let error = match Self::setup_from(found) {
+            Err(e) if missing => e,
+            result => { return result; }
+        };
+
+        for dir in fallbacks {
+            if let Ok((msg, cfg)) = Self::setup_in(dir.as_ref(), file) {
+                return Ok((format!("{} (Fallback: {})", msg, error), cfg));
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, as
+    ///     with [`setup`], but replace an existing file with a new default file
+    ///     if it was created by an older version of the application.
+    ///
+    /// The version which created a file is recorded beside it by
+    ///     [`create_versioned`], in a marker named after the file with
+    ///     `.version` appended, such as `config.toml.version`. The marker holds
+    ///     the version string on a single line. Versions made of numbers
+    ///     separated by dots, such as `1.10.2`, are compared numerically; Any
+    ///     other recorded version is outdated whenever it differs at all.
+    ///
+    /// A file with no marker is considered outdated, since it was not created
+    ///     by any version which records one. A file which is outdated is never
+    ///     simply overwritten: It is first moved aside as a backup, given a
+    ///     numbered suffix if an earlier backup already exists, so that no
+    ///     changes made by the user are lost. The message then names the
+    ///     backup. A marker for a newer version leaves the file untouched.
+    ///
+    /// This allows an application to push new defaults when it is upgraded,
+    ///     without replacing the file on every run. The `version` is typically
+    ///     `env!("CARGO_PKG_VERSION")`, but need only change when [`DEFAULT`]
+    ///     changes. Other arguments are the same as those of [`find`].
+    ///
+    /// [`create_versioned`]: Self::create_versioned
+    /// [`DEFAULT`]: Self::DEFAULT
+    /// [`find`]: Self::find
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "directories")]
+    fn setup_if_outdated(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+        version: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        use crate::version::{is_older, read};
+
+        let path = match find_path(
+            DirKind::Config,
+            qualifier, organization, application, file,
+        ) {
+            Some(path) => path,
+            None => return Err(String::from("Cannot find path for Config file.")),
+        };
+
+        let outdated = match read(&path) {
+            Ok(Some(recorded)) => is_older(&recorded, version),
+            Ok(None) => true,
+            Err(e) => return Err(format!(
+                "Cannot read version of Config file {}: {}",
+                path.display(), e,
+            )),
+        };
+
+        if !outdated && path.exists() {
+            return Self::setup_from(Self::from_path(path));
+        }
+
+        let opts = SaveOptions::default()
+            .with_backup_policy(BackupPolicy::Suffix);
+        let backup = match path.exists() {
+            true => backup_target(&path, &opts),
+            false => None,
+        };
+
+        if let Err(e) = Self::create_versioned(&path, version) {
+            return Err(format!(
+                "Cannot save {} as Config file: {}",
+                path.display(), e,
+            ));
+        }
+
+        let msg = match backup {
+            Some(backup) => format!(
+                "Replaced outdated Config file: {} (Backup: {})",
+                path.display(), backup.display(),
+            ),
+            None => SetupOutcome::Created(path.clone()).to_string(),
+        };
+
+        match Self::open(&path) {
+            ConfigOpen::FileValid(cfg) => Ok((msg, cfg.with_path(path))),
+            err => Err(open_error(&path, err)),
+        }
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, as
+    ///     with [`setup`], for a specific profile, such as `"dev"` or `"prod"`.
+    ///
+    /// The name of the profile is inserted into the filename before its
+    ///     extension, so that `config.toml` becomes `config.dev.toml`. A name
+    ///     with no extension, such as `config` or `.myapprc`, is given the
+    ///     profile as an extension instead, becoming `config.dev`. An empty
+    ///     profile selects the plain filename.
+    ///
+    /// If `fallback` is true and the file for the profile does not exist, but
+    ///     the plain file does, the plain file is used instead. If neither
+    ///     exists, a new default file is created for the profile.
+    ///
+    /// Other arguments are the same as those of [`find`].
+    ///
+    /// [`find`]: Self::find
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "directories")]
+    fn setup_profile(
+        profile: &str,
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+        fallback: bool,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        let named = profile_name(file, profile);
+        let found = Self::find(qualifier, organization, application, &named);
+
+        if fallback && matches!(found, ConfigFind::DoesNotExist(..)) {
+            let base = Self::find(qualifier, organization, application, file);
+
+            if let ConfigFind::Exists(..) = base {
+                return Self::setup_from(base);
+            }
+        }
+
+        Self::setup_from(found)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, as
+    ///     with [`setup`], taking each name as a distinct type so that they
+    ///     cannot be confused with one another.
+    ///
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "directories")]
+    fn setup_for(
+        qualifier: Qualifier,
+        organization: Organization,
+        application: Application,
+        file: FileName,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup(qualifier.0, organization.0, application.0, file.0)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, in
+    ///     a specific kind of directory.
+    ///
+    /// Arguments passed to this function are the same as those of
+    ///     [`find_with_kind`].
+    ///
+    /// [`find_with_kind`]: Self::find_with_kind
+    #[cfg(feature = "directories")]
+    fn setup_with_kind(
+        kind: DirKind,
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_from(Self::find_with_kind(
+            kind,
+            qualifier, organization, application, file,
+        ))
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, at
+    ///     the path given by a [`PathStrategy`].
+    ///
+    /// The return value has the same meaning as that of [`setup`].
+    ///
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "fs")]
+    fn setup_by(
+        strategy: impl PathStrategy,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_from(Self::find_by(strategy))
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, in
+    ///     the configuration directory of an existing [`ProjectDirs`].
+    ///
+    /// This is useful for programs which also use the same [`ProjectDirs`] to
+    ///     find their data and cache directories, since they need only be found
+    ///     once. Otherwise, this is the same as [`setup`].
+    ///
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "directories")]
+    fn setup_with_dirs(
+        dirs: &ProjectDirs,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_in(dirs.config_dir(), file)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup,
+    ///     within a specific directory rather than one found by [`ProjectDirs`].
+    ///
+    /// This is useful for tests, which may use a temporary directory in order
+    ///     to avoid touching the real configuration of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The directory in which to place the Configuration file. It
+    ///     will be created if it does not exist.
+    /// * `file`: The filename to be used for the Configuration file.
+    #[cfg(feature = "fs")]
+    fn setup_in(
+        dir: &Path,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_from(Self::from_path(dir.join(file)))
+    }
+
+    /// Complete configuration setup from the result of a search, creating a
+    ///     new default file if none was found.
+    ///
+    /// The return value has the same meaning as that of [`setup`].
+    ///
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "fs")]
+    fn setup_from(
+        found: ConfigFind<Self>,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        Self::setup_outcome(found).map(|(out, cfg)| (out.to_string(), cfg))
+    }
+
+    /// Complete configuration setup from the result of a search, as with
+    ///     [`setup_from`], describing the action taken with a [`SetupOutcome`]
+    ///     rather than a string.
+    ///
+    /// [`setup_from`]: Self::setup_from
+    #[cfg(feature = "fs")]
+    fn setup_outcome(
+        found: ConfigFind<Self>,
+    ) -> Result<(SetupOutcome, ConfigFile<Self>), String> {
+        complete_setup::<Self, F>(found, Self::open)
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup, as
+    ///     with [`setup`], but without passing the configuration through
+    ///     [`prepare`].
+    ///
+    /// This is intended for tools, such as migration utilities, which need to
+    ///     inspect the configuration exactly as it was written.
+    ///
+    /// [`prepare`]: Self::prepare
+    /// [`setup`]: Self::setup
+    #[cfg(feature = "directories")]
+    fn setup_raw(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        let path = find_path(
+            DirKind::Config,
+            qualifier, organization, application, file,
+        );
+
+        let found = match path {
+            None => ConfigFind::NoPath,
+            Some(path) if !path.exists() => ConfigFind::DoesNotExist(path),
+            Some(path) => {
+                let open = Self::open_raw(&path);
+                ConfigFind::Exists(path, open)
+            }
+        };
+
+        complete_setup::<Self, F>(found, Self::open_raw)
+            .map(|(out, cfg)| (out.to_string(), cfg))
+    }
+
+    /// Attempt to automatically handle all aspects of configuration setup.
+    ///
+    /// If the target file exists, but is invalid, it will be overwritten with a
+    ///     new default file.
+    ///
+    /// Arguments passed to this function are the same as those of [`find`].
+    ///
+    /// [`find`]: Self::find
+    #[cfg(feature = "directories")]
+    fn setup_replace_invalid(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+        file: &str,
+    ) -> Result<(String, ConfigFile<Self>), String> {
+        use ConfigFind::*;
+
+        match Self::find(qualifier, organization, application, file) {
+            Exists(path, ConfigOpen::FileInvalid(_) | ConfigOpen::FileEmpty)
+            | DoesNotExist(path) => match Self::create(&path, true, true) {
+                Err(e) => Err(format!(
+                    "Cannot save {} as Config file: {}",
+                    path.display(), e,
+                )),
+                Ok(..) => match Self::open(&path) {
+                    ConfigOpen::FileValid(cfg) => Ok((
+                        format!("Created new Config file: {}", path.display()),
+                        cfg.with_path(path),
+                    )),
+                    err => Err(open_error(&path, err)),
+                }
+            }
+            Exists(path, cfg) => match cfg {
+                ConfigOpen::FileValid(cfg) => Ok((
+                    format!("Using existing Config file: {}", path.display()),
+                    cfg.with_path(path),
+                )),
+                err => Err(open_error(&path, err)),
+            }
+            Inaccessible(path, e) => {
+                Err(open_error(&path, ConfigOpen::<Self>::FileInaccessible(e)))
+            }
+            NoPath => Err(String::from("Cannot find path for Config file.")),
+        }
+    }
+}
+
+
+/// A pairing of a configuration state with the file path at which it is saved.
+///
+/// The location of the file may be replaced with any other [`ConfigStorage`],
+///     such as an [`InMemory`] buffer.
+#[derive(Clone)]
+pub struct ConfigFile<Cfg, S = PathBuf> {
+    /// The stored configuration state.
+    pub data: Cfg,
+    /// The path to the file associated with this configuration state.
+    pub path: S,
+}
+
+
+impl<Cfg> ConfigFile<Cfg> {
+    /// Associate a configuration state with a file path.
+    pub fn new(data: Cfg, path: impl Into<PathBuf>) -> Self {
+        Self { data, path: path.into() }
+    }
+
+    /// Open the file in a text editor chosen by the user, and wait for the
+    ///     editor to exit.
+    ///
+    /// The editor is taken from the `VISUAL` or `EDITOR` environment variables,
+    ///     in that order. If the whole value names an existing file, that file
+    ///     is run, so that the path may contain spaces, as is common on
+    ///     Windows. Otherwise, the value is split on whitespace into a program
+    ///     and its arguments, such as `code --wait`; Quotes are not recognized.
+    ///     If neither is set, the default text editor of the platform is used:
+    ///     Notepad on Windows, TextEdit on macOS, and `vi` elsewhere. An error
+    ///     is returned if the editor cannot be started, or exits unsuccessfully.
+    ///
+    /// The stored data is not changed; Call [`reload`] afterwards in order to
+    ///     read the edited file.
+    ///
+    /// [`reload`]: Self::reload
+    #[cfg(feature = "fs")]
+    pub fn open_in_editor(&self) -> Result<(), std::io::Error> {
+        use std::{env::var, io::Error, process::Command};
+
+        let editor = var("VISUAL").or_else(|_| var("EDITOR"))
+            .ok()
+            .filter(|editor| !editor.trim().is_empty());
+
+        let mut command = match &editor {
+            Some(editor) if Path::new(editor).is_file() => Command::new(editor),
+            Some(editor) => {
+                let mut words = editor.split_whitespace();
+                let mut command = Command::new(words.next().unwrap_or_default());
+
+                command.args(words);
+                command
+            }
+            None if cfg!(windows) => Command::new("notepad"),
+            None if cfg!(target_os = "macos") => {
+                let mut command = Command::new("open");
+
+                command.args(["-W", "-t"]);
+                command
+            }
+            None => Command::new("vi"),
+        };
+
+        let status = command.arg(&self.path).status()?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(Error::other(
+                format!("Editor exited unsuccessfully: {}", status),
+            )),
+        }
+    }
+
+    /// Make the file read-only, or writable again, as with the [`read_only`]
+    ///     option.
+    ///
+    /// A file which was made read-only should be made writable again before
+    ///     it is saved without a backup, since it cannot otherwise be replaced.
+    ///
+    /// [`read_only`]: SaveOptions::read_only
+    #[cfg(feature = "fs")]
+    pub fn set_read_only(&self, read_only: bool) -> Result<(), std::io::Error> {
+        crate::storage::set_read_only(&self.path, read_only)
+    }
+
+    /// Return the path at which a backup of the file would be saved by [`save`],
+    ///     or [`None`] if no backup would be saved, such as when the file does
+    ///     not exist yet.
+    ///
+    /// [`save`]: Self::save
+    #[cfg(feature = "fs")]
+    pub fn backup_path(&self) -> Option<PathBuf> {
+        self.backup_path_with(&SaveOptions::default())
+    }
+
+    /// Return the path at which a backup of the file would be saved by saving
+    ///     with the given options, taking into account the [`backup_dir`],
+    ///     [`backup_policy`] and [`follow_symlinks`] options. Returns [`None`]
+    ///     if no backup would be saved, such as when the file does not exist
+    ///     yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config {}
+    /// use tomlconf::{ConfigFile, SaveOptions};
+    ///
+    /// let dir = std::env::temp_dir().join("tomlconf-backup-path");
+    /// let file = ConfigFile::new(Config {}, dir.join("config.toml"));
+    /// # std::fs::remove_dir_all(&dir).ok();
+    /// assert_eq!(file.backup_path(), None);
+    ///
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(&file.path, "").unwrap();
+    /// assert_eq!(file.backup_path(), Some(dir.join(".bkp.config.toml")));
+    ///
+    /// let opts = SaveOptions::default().with_backup_dir("old");
+    /// assert_eq!(
+    ///     file.backup_path_with(&opts),
+    ///     Some(dir.join("old/.bkp.config.toml")),
+    /// );
+    /// # std::fs::remove_dir_all(&dir).ok();
+    /// ```
+    ///
+    /// [`backup_dir`]: SaveOptions::backup_dir
+    /// [`backup_policy`]: SaveOptions::backup_policy
+    /// [`follow_symlinks`]: SaveOptions::follow_symlinks
+    #[cfg(feature = "fs")]
+    pub fn backup_path_with(&self, opts: &SaveOptions) -> Option<PathBuf> {
+        if !opts.create_backup {
+            return None;
+        }
+
+        let path = match opts.follow_symlinks {
+            true => resolve_link(&self.path),
+            false => Cow::Borrowed(self.path.as_path()),
+        };
+
+        match path.exists() {
+            true => backup_target(&path, opts),
+            false => None,
+        }
+    }
+
+    /// Return the time at which the file was last modified, following any
+    ///     symbolic link at its path.
+    #[cfg(feature = "fs")]
+    pub fn modified(&self) -> Result<std::time::SystemTime, std::io::Error> {
+        self.path.metadata()?.modified()
+    }
+
+    /// Delete the backups of the file which were last modified longer ago than
+    ///     the given duration. Return the number of backups deleted.
+    ///
+    /// Backups are looked for in the default location; Use
+    ///     [`prune_backups_with`] if they are kept in a [`backup_dir`].
+    ///
+    /// [`backup_dir`]: SaveOptions::backup_dir
+    /// [`prune_backups_with`]: Self::prune_backups_with
+    #[cfg(feature = "fs")]
+    pub fn prune_backups(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<usize, std::io::Error> {
+        self.prune_backups_with(max_age, &SaveOptions::default())
+    }
+
+    /// Delete the backups of the file which were last modified longer ago than
+    ///     the given duration, looking for them where they would be placed by
+    ///     saving with the given options. Return the number of backups deleted.
+    ///
+    /// This includes the numbered backups kept by [`BackupPolicy::Suffix`],
+    ///     such as `.bkp.config.toml.2`. Other files in the same directory are
+    ///     left alone. A backup whose modification time cannot be determined,
+    ///     or lies in the future, is also left alone, as is one which is
+    ///     removed by another process while the directory is being searched.
+    ///
+    /// The age of a backup is measured from its modification time, not from
+    ///     the time at which it was made. Since a backup usually keeps the
+    ///     modification time of the version of the file which it preserves, a
+    ///     backup made recently of a file which had not been changed for a
+    ///     long time may be deleted at once.
+    #[cfg(feature = "fs")]
+    pub fn prune_backups_with(
+        &self,
+        max_age: std::time::Duration,
+        opts: &SaveOptions,
+    ) -> Result<usize, std::io::Error> {
+        use std::{ffi::OsStr, fs::{read_dir, remove_file}, io::ErrorKind};
+
+        let backup = match get_backup(&self.path, opts.backup_dir.as_deref()) {
+            Some(backup) => backup,
+            None => return Ok(0),
+        };
+
+        let base = backup.file_name().unwrap_or_default();
+        let is_backup = |name: &OsStr| {
+            name == base || match (name.to_str(), base.to_str()) {
+                (Some(name), Some(base)) => name.strip_prefix(base)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .is_some_and(|n| {
+                        !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())
+                    }),
+                _ => false,
+            }
+        };
+
+        let dir = match backup.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let entries = match read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut pruned = 0;
+
+        for entry in entries {
+            let entry = entry?;
+
+            if !is_backup(&entry.file_name()) {
+                continue;
+            }
+
+            let meta = match entry.metadata() {
+                Ok(meta) => meta,
+                Err(_) => continue,
+            };
+            let age = meta.modified().ok().and_then(|time| time.elapsed().ok());
+
+            if meta.is_file() && age.is_some_and(|age| age > max_age) {
+                match remove_file(entry.path()) {
+                    Ok(()) => pruned += 1,
+                    Err(e) if e.kind() == ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(pruned)
+    }
+}
+
+
+impl<Cfg> ConfigFile<Cfg, InMemory> {
+    /// Associate a configuration state with an empty [`InMemory`] buffer, which
+    ///     will hold its text once it is saved.
+    pub fn in_memory(data: Cfg) -> Self {
+        Self { data, path: InMemory::default() }
+    }
+
+    /// Return a copy of the text most recently saved into the buffer.
+    pub fn contents(&self) -> String {
+        self.path.text()
+    }
+}
+
+
+impl<Cfg, S: ConfigStorage> ConfigFile<Cfg, S> {
+    /// Read from the file associated with this configuration, and replace the
+    ///     stored data.
+    #[cfg(not(feature = "nightly"))]
+    pub fn reload<F: ConfigFormat>(&mut self) -> Result<(), ConfigOpen<Cfg>>
+        where Cfg: ConfigData<F>
+    {
+        // use std::mem::replace;
+
+        match Cfg::open_storage(&self.path) {
+            // ConfigOpen::FileValid(new) => Ok(replace(&mut self.data, new)),
+            ConfigOpen::FileValid(new) => {
+                self.data = new;
+                Ok(())
+            }
+            err => Err(err),
+        }
+    }
+
+    /// Read from the file associated with this configuration, and replace the
+    ///     stored data.
+    #[cfg(feature = "nightly")]
+    pub fn reload<F: ConfigFormat>(&mut self) -> Result<(), ConfigOpen<Cfg>>
+        where Cfg: ConfigData<F>
+    {
+        self.data = Cfg::open_storage(&self.path)?;
+        Ok(())
+
+        // Ok(std::mem::replace(&mut self.data, Cfg::open(&self.path)?))
+    }
+
+    /// Read from the file associated with this configuration, and replace the
+    ///     stored data, choosing the format from the extension of the file.
+    ///
+    /// The extension is checked again every time this is called, so a file
+    ///     which has been renamed from `config.toml` to `config.json` is read
+    ///     as JSON from then on. A file whose extension is `json`, ignoring any
+    ///     further `gz` extension, is read as [`Json`]; Any other file, or any
+    ///     storage which is not a file, is read as [`Toml`].
+    ///
+    /// [`Json`]: crate::Json
+    #[cfg(feature = "json")]
+    pub fn reload_detect(&mut self) -> Result<(), ConfigOpen<Cfg>>
+        where Cfg: ConfigData<Toml> + ConfigData<crate::Json>
+    {
+        let is_json = self.path.path().is_some_and(|path| {
+            let path = match path.extension() {
+                Some(ext) if ext.eq_ignore_ascii_case("gz") => {
+                    Path::new(path.file_stem().unwrap_or_default())
+                }
+                _ => path,
+            };
+
+            path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        });
+
+        let open = match is_json {
+            true => <Cfg as ConfigData<crate::Json>>::open_storage(&self.path),
+            false => <Cfg as ConfigData<Toml>>::open_storage(&self.path),
+        };
+
+        self.data = open.into_result()?;
+        Ok(())
+    }
+
+    /// Read from the file associated with this configuration, and replace the
+    ///     stored data, but only if the file has been modified since the time
+    ///     held in `last`.
+    ///
+    /// Unlike [`reload`], this never fails; The result tells whether the data
+    ///     was replaced, was already up to date, or could not be read. This
+    ///     suits a loop which polls the file and reacts only to changes, since
+    ///     an unmodified file is not read or parsed again.
+    ///
+    /// Changes are detected by the modification time of the file, which is
+    ///     kept by the caller in `last`, and updated whenever the file is read
+    ///     successfully. It should start as the time returned by [`modified`]
+    ///     when the file was opened; If it is [`None`], the file is always
+    ///     read, as it is for a storage which has no modification time, such
+    ///     as an [`InMemory`] buffer. A file modified twice within the
+    ///     resolution of the clock of its filesystem may not be read the
+    ///     second time. If the file cannot be read, `last` is not changed, so
+    ///     it is tried again by the next call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+    /// use tomlconf::{ConfigData, ReloadOutcome};
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 3";
+    /// }
+    ///
+    /// let (_, mut file) = Config::setup(
+    ///     "com", "Example", "App", "config.toml",
+    /// ).unwrap();
+    /// let mut last = file.modified().ok();
+    ///
+    /// loop {
+    ///     std::thread::sleep(std::time::Duration::from_secs(5));
+    ///
+    ///     match file.try_reload(&mut last) {
+    ///         ReloadOutcome::Reloaded => println!("{}", file.number),
+    ///         ReloadOutcome::Failed(e) => eprintln!("{}", e),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`modified`]: ConfigFile::modified
+    /// [`reload`]: Self::reload
+    pub fn try_reload<F: ConfigFormat>(
+        &mut self,
+        last: &mut Option<std::time::SystemTime>,
+    ) -> ReloadOutcome<Cfg>
+        where Cfg: ConfigData<F>
+    {
+        let modified = self.path.path()
+            .and_then(|path| path.metadata().ok())
+            .and_then(|meta| meta.modified().ok());
+
+        if modified.is_some() && modified == *last {
+            return ReloadOutcome::Unchanged;
+        }
+
+        match Cfg::open_storage(&self.path) {
+            ConfigOpen::FileValid(new) => {
+                self.data = new;
+                *last = modified;
+                ReloadOutcome::Reloaded
+            }
+            err => ReloadOutcome::Failed(err),
+        }
+    }
+
+    /// Read from the file associated with this configuration, and replace the
+    ///     stored data, without passing the new data through [`prepare`].
+    ///
+    /// [`prepare`]: ConfigData::prepare
+    pub fn reload_raw<F: ConfigFormat>(&mut self) -> Result<(), ConfigOpen<Cfg>>
+        where Cfg: ConfigData<F>
+    {
+        let text = self.path.read().map_err(ConfigOpen::FileInaccessible)?;
+        let text = fill_empty::<Cfg, F>(text).ok_or(ConfigOpen::FileEmpty)?;
+
+        self.data = Cfg::from_text_raw(text).map_err(ConfigOpen::FileInvalid)?;
+        Ok(())
+    }
+
+    /// Read from the file associated with this configuration, and return only
+    ///     the value of a single top-level key, as a separate type. The stored
+    ///     data is not changed.
+    ///
+    /// This allows a part of a program to poll only its own section of the
+    ///     file, without reconstructing the whole configuration. The value is
+    ///     not passed through [`prepare`], but an empty file is handled as it
+    ///     is by [`open`], and the bindings of [`env_bindings`] are applied.
+    ///     Since a binding can only be applied to the whole configuration, the
+    ///     whole file is parsed if any bound variable is set.
+    ///
+    /// This is only available for configurations stored as [`Toml`].
+    ///
+    /// [`env_bindings`]: ConfigData::env_bindings
+    /// [`open`]: ConfigData::open
+    /// [`prepare`]: ConfigData::prepare
+    pub fn reload_field<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<T, ConfigOpen<T>>
+        where Cfg: ConfigData<Toml> + Serialize
+    {
+        use serde::de::Error;
+
+        let text = self.path.read().map_err(ConfigOpen::FileInaccessible)?;
+        let text = fill_empty::<Cfg, Toml>(text).ok_or(ConfigOpen::FileEmpty)?;
+        let invalid = |e, text| {
+            ConfigOpen::FileInvalid(ParseError::new::<Toml>(e, text))
+        };
+
+        let bound = Cfg::env_bindings().iter()
+            .any(|binding| std::env::var_os(binding.var()).is_some());
+
+        let parsed = match bound {
+            false => toml::from_str::<toml::value::Table>(&text),
+            true => {
+                let cfg = Cfg::from_text_raw(text.clone())
+                    .map_err(ConfigOpen::FileInvalid)?
+                    .apply_env()
+                    .map_err(ConfigOpen::EnvInvalid)?;
+
+                match toml::Value::try_from(&cfg) {
+                    Ok(toml::Value::Table(table)) => Ok(table),
+                    Ok(_) => Err(Error::custom("expected a table")),
+                    Err(e) => Err(Error::custom(e)),
+                }
+            }
+        };
+
+        let mut table = match parsed {
+            Ok(table) => table,
+            Err(e) => { return Err(invalid(e, text)); }
+        };
+
+        match table.remove(key) {
+            Some(value) => value.try_into().map_err(|e| invalid(e, text)),
+            None => {
+                let e = Error::custom(format!("missing field `{}`", key));
+                Err(invalid(e, text))
+            }
+        }
+    }
+
+    /// Write the configuration into a new file at the associated path.
+    pub fn save<F: ConfigFormat>(
+        &self,
+        create_backup: bool,
+        create_parent: bool,
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.save_with(&SaveOptions::new(create_backup, create_parent))
+    }
+
+    /// Write the configuration into a new file at the associated path, using a
+    ///     full set of options.
+    pub fn save_with<F: ConfigFormat>(
+        &self,
+        opts: &SaveOptions,
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.save_as(&self.path, opts)
+    }
+
+    /// Write the configuration into the file at the associated path, as with
+    ///     [`save`], and wait until it has durably reached the storage device,
+    ///     by setting the [`sync`] option.
+    ///
+    /// This may be much slower than [`save`], since it waits for the device to
+    ///     confirm the write, and so is best used at chosen points, such as
+    ///     after a batch of changes, rather than after every change.
+    ///
+    /// [`save`]: Self::save
+    /// [`sync`]: SaveOptions::sync
+    pub fn flush<F: ConfigFormat>(&self) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.save_with(&SaveOptions::default().with_sync(true))
+    }
+
+    /// Change the configuration with a function, and then immediately write it
+    ///     into the file at the associated path, as with [`save_with`], using
+    ///     the default options and an [`atomic`] write.
+    ///
+    /// The changes are kept in memory even if the write fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize, serde::Serialize)]
+    /// # struct Config { number: u32 }
+    /// use tomlconf::{ConfigData, ConfigFile};
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 3";
+    /// }
+    ///
+    /// let mut file = ConfigFile::in_memory(Config { number: 3 });
+    /// file.update(|cfg: &mut Config| cfg.number += 1).unwrap();
+    ///
+    /// assert_eq!(file.number, 4);
+    /// assert_eq!(file.contents(), "number = 4\n");
+    /// ```
+    ///
+    /// [`atomic`]: SaveOptions::atomic
+    /// [`save_with`]: Self::save_with
+    pub fn update<F: ConfigFormat>(
+        &mut self,
+        f: impl FnOnce(&mut Cfg),
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.update_with(&SaveOptions::default().with_atomic(true), f)
+    }
+
+    /// Change the configuration with a function, and then immediately write it
+    ///     into the file at the associated path, as with [`update`], using a
+    ///     full set of options.
+    ///
+    /// [`update`]: Self::update
+    pub fn update_with<F: ConfigFormat>(
+        &mut self,
+        opts: &SaveOptions,
+        f: impl FnOnce(&mut Cfg),
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        f(&mut self.data);
+        self.save_with(opts)
+    }
+
+    /// Write the configuration into a different place, such as another file,
+    ///     leaving the associated path unchanged. This may be used to export a
+    ///     copy of the configuration.
+    ///
+    /// The options are applied to the new location, so that a backup is made
+    ///     of any file already there, if requested.
+    pub fn save_as<F: ConfigFormat, T: ConfigStorage + ?Sized>(
+        &self,
+        path: &T,
+        opts: &SaveOptions,
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        let serial = F::to_string_with(&self.data, opts).map_err(|e| {
+            ConfigSaveError::SerializeFailure(F::explain_error(&self.data, e))
+        })?;
+        Ok(path.write(&opts.normalize(serial), opts)?)
+    }
+
+    /// Write the configuration into a different place, as with [`save_as`],
+    ///     and then associate the configuration with it, so that it is used by
+    ///     later saves and reloads. If the write fails, the associated path is
+    ///     unchanged.
+    ///
+    /// [`save_as`]: Self::save_as
+    pub fn save_as_and_switch<F: ConfigFormat>(
+        &mut self,
+        path: S,
+        opts: &SaveOptions,
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.save_as(&path, opts)?;
+        self.path = path;
+        Ok(())
+    }
+}
+
+
+impl<Cfg, S> ConfigFile<Cfg, S> {
+    /// Convert the configuration into text, as it would be written by [`save`]
+    ///     with the default options.
+    ///
+    /// [`save`]: Self::save
+    pub fn dump<F: ConfigFormat>(&self) -> Result<String, F::SerError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        self.dump_with(&SaveOptions::default())
+    }
+
+    /// Convert the configuration into text, as it would be written by
+    ///     [`save_with`] with the given options.
+    ///
+    /// [`save_with`]: Self::save_with
+    pub fn dump_with<F: ConfigFormat>(
+        &self,
+        opts: &SaveOptions,
+    ) -> Result<String, F::SerError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        F::to_string_with(&self.data, opts).map(|text| opts.normalize(text))
+    }
+
+    /// Convert a redacted view of the configuration into text, suitable for
+    ///     logging or for inclusion in a support bundle.
+    ///
+    /// The type `R` is a view provided by the application, built from the
+    ///     configuration with any sensitive fields replaced; The [`REDACTED`]
+    ///     placeholder is provided for this purpose. The view is serialized in
+    ///     the same format as the configuration itself.
+    pub fn dump_redacted<R, F: ConfigFormat>(&self) -> Result<String, F::SerError>
+        where
+            Cfg: ConfigData<F>,
+            R: for<'a> From<&'a Cfg> + Serialize,
+    {
+        F::to_string(&R::from(&self.data))
+    }
+
+    /// Replace the stored data with the default configuration, as parsed by
+    ///     [`ConfigData::default`], keeping the associated path. Nothing is
+    ///     written until the configuration is saved.
+    ///
+    /// If the default cannot be parsed, the stored data is left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+    /// use std::path::Path;
+    /// use tomlconf::{ConfigData, ConfigFile};
+    ///
+    /// impl ConfigData for Config {
+    ///     const DEFAULT: &'static str = "number = 3";
+    /// }
+    ///
+    /// let mut file = ConfigFile::new(Config { number: 9 }, "config.toml");
+    /// file.reset_to_default().unwrap();
+    ///
+    /// assert_eq!(file.number, 3);
+    /// assert_eq!(file.path, Path::new("config.toml"));
+    /// ```
+    pub fn reset_to_default<F: ConfigFormat>(&mut self) -> Result<(), F::DeError>
+        where Cfg: ConfigData<F>
+    {
+        self.data = Cfg::default()?;
+        Ok(())
+    }
+
+    /// Return `true` if the stored data is the same as the default
+    ///     configuration, as parsed by [`ConfigData::default`].
+    ///
+    /// Both are compared in the form they take when converted into TOML values,
+    ///     so differences which would not be visible in a saved file, such as
+    ///     the order of entries in a map, are ignored. If either cannot be
+    ///     converted, or the default cannot be parsed, this returns `false`.
+    ///
+    /// This is only available for configurations stored as [`Toml`].
+    pub fn is_default(&self) -> bool
+        where Cfg: ConfigData<Toml> + Serialize
+    {
+        let default = match <Cfg as ConfigData<Toml>>::default() {
+            Ok(default) => default,
+            Err(..) => { return false; }
+        };
+
+        use toml::Value;
+
+        match (Value::try_from(&self.data), Value::try_from(default)) {
+            (Ok(data), Ok(default)) => data == default,
+            _ => false,
+        }
+    }
+}
+
+
+impl<Cfg, S> Deref for ConfigFile<Cfg, S> {
+    type Target = Cfg;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+
+impl<Cfg, S> DerefMut for ConfigFile<Cfg, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use serde::Deserialize;
+    use crate::testing::scratch;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Config { number: u32 }
+
+    impl ConfigData for Config {
+        const DEFAULT: &'static str = "number = 3";
+    }
+
+    #[test]
+    fn update_persists_changes() {
+        let path = scratch("update").join("config.toml");
+        Config::create(&path, false, true).unwrap();
+
+        let mut file = Config::open(&path).into_config().unwrap()
+            .with_path(path.clone());
+
+        #[cfg(unix)] {
+            use std::os::unix::fs::PermissionsExt;
+            let perms = std::fs::Permissions::from_mode(0o600);
+            std::fs::set_permissions(&path, perms).unwrap();
+        }
+
+        file.update(|cfg| cfg.number = 5).unwrap();
+        let saved = Config::open(&path).into_config();
+        assert_eq!(saved, Some(Config { number: 5 }));
+
+        #[cfg(unix)] {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = path.metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn prune_numbered_backups() {
+        use std::time::Duration;
+
+        let dir = scratch("prune");
+        let path = dir.join("b.toml");
+        let opts = SaveOptions::default()
+            .with_backup_policy(BackupPolicy::Suffix);
+
+        for _ in 0..4 {
+            Config::create_with(&path, &opts).unwrap();
+        }
+
+        std::fs::write(dir.join(".bkp.b.toml.old"), "").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let file = Config { number: 3 }.with_path(path);
+        let pruned = file.prune_backups_with(Duration::from_millis(1), &opts);
+        assert_eq!(pruned.unwrap(), 3);
+
+        let mut names: Vec<_> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, [".bkp.b.toml.old", "b.toml"]);
+    }
+
+    #[test]
+    fn backup_names() {
+        let backup = |path: &str| get_backup(Path::new(path), None).unwrap();
+
+        assert_eq!(backup("config.toml"), Path::new(".bkp.config.toml"));
+        assert_eq!(backup(".myapprc"), Path::new(".myapprc.bkp"));
+        assert_eq!(backup("dir/config"), Path::new("dir/config.bkp"));
+        assert_eq!(
+            backup("config.local.toml"),
+            Path::new(".bkp.config.local.toml"),
+        );
+        assert_eq!(
+            get_backup(Path::new("dir/config.toml"), Some(Path::new("old"))),
+            Some(PathBuf::from("dir/old/.bkp.config.toml")),
+        );
+    }
+
+    /// Save three versions of a file with a backup policy, returning a function
+    ///     to read any file in the same directory.
+    fn save_three(
+        name: &str,
+        policy: BackupPolicy,
+    ) -> impl Fn(&str) -> Option<String> {
+        let dir = scratch(name);
+        let path = dir.join("config.toml");
+        let opts = SaveOptions::default().with_backup_policy(policy);
+
+        for text in ["a = 1", "a = 2", "a = 3"] {
+            path.as_path().write(text, &opts).unwrap();
+        }
+
+        move |name| std::fs::read_to_string(dir.join(name)).ok()
+    }
+
+    #[test]
+    fn backup_policy_overwrite() {
+        let read = save_three("overwrite", BackupPolicy::Overwrite);
+
+        assert_eq!(read("config.toml").unwrap(), "a = 3");
+        assert_eq!(read(".bkp.config.toml").unwrap(), "a = 2");
+        assert_eq!(read(".bkp.config.toml.1"), None);
+    }
+
+    #[test]
+    fn backup_policy_skip() {
+        let read = save_three("skip", BackupPolicy::Skip);
+
+        assert_eq!(read("config.toml").unwrap(), "a = 3");
+        assert_eq!(read(".bkp.config.toml").unwrap(), "a = 1");
+        assert_eq!(read(".bkp.config.toml.1"), None);
+    }
+
+    #[test]
+    fn backup_policy_suffix() {
+        let read = save_three("suffix", BackupPolicy::Suffix);
+
+        assert_eq!(read("config.toml").unwrap(), "a = 3");
+        assert_eq!(read(".bkp.config.toml").unwrap(), "a = 1");
+        assert_eq!(read(".bkp.config.toml.1").unwrap(), "a = 2");
+        assert_eq!(read(".bkp.config.toml.2"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn reload_detects_renamed_format() {
+        use std::fs::{rename, write};
+
+        #[derive(Deserialize)]
+        struct Detect { number: u32 }
+
+        impl ConfigData for Detect {
+            const DEFAULT: &'static str = "number = 3";
+        }
+
+        impl ConfigData<crate::Json> for Detect {
+            const DEFAULT: &'static str = r#"{"number": 3}"#;
+        }
+
+        let dir = scratch("detect");
+        let (toml, json) = (dir.join("config.toml"), dir.join("config.json"));
+        let mut file = ConfigFile::new(Detect { number: 0 }, &toml);
+
+        write(&toml, "number = 1").unwrap();
+        file.reload_detect().ok().unwrap();
+        assert_eq!(file.number, 1);
+
+        rename(&toml, &json).unwrap();
+        write(&json, r#"{"number": 2}"#).unwrap();
+        file.path = json.clone();
+        file.reload_detect().ok().unwrap();
+        assert_eq!(file.number, 2);
+
+        rename(&json, &toml).unwrap();
+        write(&toml, "number = 4").unwrap();
+        file.path = toml;
+        file.reload_detect().ok().unwrap();
+        assert_eq!(file.number, 4);
+    }
+
+    #[test]
+    fn try_reload_skips_unmodified_file() {
+        use std::time::{Duration, SystemTime};
+
+        let path = scratch("try-reload").join("config.toml");
+        std::fs::write(&path, "number = 1").unwrap();
+
+        let mut file = Config::open(&path).into_config().unwrap()
+            .with_path(path.clone());
+        let mut last = file.modified().ok();
+        assert!(matches!(file.try_reload(&mut last), ReloadOutcome::Unchanged));
+
+        //  Replace the file with invalid text, keeping its modification time,
+        //      so that it would fail if it were read again.
+        std::fs::write(&path, "number = ?").unwrap();
+        std::fs::File::options().write(true).open(&path).unwrap()
+            .set_modified(last.unwrap()).unwrap();
+        assert!(matches!(file.try_reload(&mut last), ReloadOutcome::Unchanged));
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::write(&path, "number = 2").unwrap();
+        std::fs::File::options().write(true).open(&path).unwrap()
+            .set_modified(later).unwrap();
+        assert!(file.try_reload(&mut last).is_reloaded());
+        assert_eq!((file.number, last), (2, Some(later)));
+
+        let mut never = None;
+        assert!(file.try_reload(&mut never).is_reloaded());
+    }
+
+    #[test]
+    fn reload_raw_reports_empty_file() {
+        let path = scratch("reload-raw").join("config.toml");
+        std::fs::write(&path, " \n").unwrap();
+
+        let mut file = ConfigFile::new(Config { number: 0 }, &path);
+        let result = file.reload_raw();
+        assert!(matches!(result, Err(ConfigOpen::FileEmpty)));
+    }
+
+    #[test]
+    fn reload_field_applies_env() {
+        #[derive(Deserialize, Serialize)]
+        struct Bound { name: String, number: u32 }
+
+        impl ConfigData for Bound {
+            const DEFAULT: &'static str = "name = 'a'\nnumber = 3";
+            const EMPTY_IS_DEFAULT: bool = true;
+
+            fn env_bindings() -> Vec<EnvBinding<Self>> {
+                vec![EnvBinding::new(
+                    "TOMLCONF_TEST_RELOAD_FIELD", "number",
+                    |cfg: &mut Self| &mut cfg.number,
+                )]
+            }
+        }
+
+        let path = scratch("reload-field").join("config.toml");
+        let file = ConfigFile::new(Bound::default().unwrap(), &path);
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(file.reload_field::<u32>("number").ok(), Some(3));
+
+        std::fs::write(&path, "name = 'b'\nnumber = 1").unwrap();
+        std::env::set_var("TOMLCONF_TEST_RELOAD_FIELD", "7");
+        assert_eq!(file.reload_field::<u32>("number").ok(), Some(7));
+        assert_eq!(file.reload_field::<String>("name").ok(), Some("b".into()));
     }
 }
@@ -0,0 +1,131 @@
+use std::{
+    env::var_os,
+    fmt::{Display, Formatter, self},
+    path::{Path, PathBuf},
+};
+use directories::ProjectDirs;
+use crate::config::DirKind;
+
+
+/// An explanation of why [`ProjectDirs`] placed the configuration directory of
+///     an application where it did, intended to be shown to users who need to
+///     find out why their configuration is read from a particular place.
+///
+/// Alongside the directory itself, this reports the environment variable which
+///     determined it, if any, and the convention of the platform which was
+///     followed. These are derived by inspecting the environment in the same
+///     way as [`ProjectDirs`], and so describe the current process.
+///
+/// This requires the `diagnostics` feature.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirDiagnosis {
+    path: PathBuf,
+    variable: Option<&'static str>,
+    convention: &'static str,
+}
+
+impl DirDiagnosis {
+    /// Resolve and explain a kind of configuration directory of an application.
+    ///     Returns [`None`] if no valid home directory could be found.
+    ///
+    /// Arguments following `kind` are the same as the first three arguments of
+    ///     [`ConfigData::find`].
+    ///
+    /// [`ConfigData::find`]: crate::ConfigData::find
+    pub fn new(
+        kind: DirKind,
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+    ) -> Option<Self> {
+        let dirs = ProjectDirs::from(qualifier, organization, application)?;
+        let (variable, convention) = explain(kind);
+
+        Some(Self { path: kind.select(&dirs).to_owned(), variable, convention })
+    }
+
+    /// Return the path of the directory.
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Return the name of the environment variable which determined the
+    ///     location of the directory, if any.
+    pub fn variable(&self) -> Option<&'static str> { self.variable }
+
+    /// Return a description of the convention of the platform which placed the
+    ///     directory where it is.
+    pub fn convention(&self) -> &'static str { self.convention }
+}
+
+
+impl Display for DirDiagnosis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        match self.variable {
+            Some(var) if cfg!(windows) => {
+                write!(f, " (using %{}%: {})", var, self.convention)
+            }
+            Some(var) => write!(f, " (using ${}: {})", var, self.convention),
+            None => write!(f, " ({})", self.convention),
+        }
+    }
+}
+
+
+/// Return whether an environment variable is set to a usable value.
+#[cfg(not(windows))]
+fn is_set(var: &str, absolute: bool) -> bool {
+    var_os(var).is_some_and(|value| match absolute {
+        true => Path::new(&value).is_absolute(),
+        false => !value.is_empty(),
+    })
+}
+
+
+/// Identify the environment variable and convention which determine a kind of
+///     configuration directory on the current platform.
+#[cfg(windows)]
+fn explain(kind: DirKind) -> (Option<&'static str>, &'static str) {
+    match kind {
+        DirKind::Config => (
+            Some("APPDATA"),
+            "the Roaming AppData known folder of Windows",
+        ),
+        DirKind::ConfigLocal => (
+            Some("LOCALAPPDATA"),
+            "the Local AppData known folder of Windows",
+        ),
+    }
+}
+
+
+/// Identify the environment variable and convention which determine a kind of
+///     configuration directory on the current platform.
+#[cfg(target_os = "macos")]
+fn explain(_kind: DirKind) -> (Option<&'static str>, &'static str) {
+    const CONVENTION: &str = "Application Support within the home directory, \
+        following the guidelines of Apple";
+
+    match is_set("HOME", false) {
+        true => (Some("HOME"), CONVENTION),
+        false => (None, CONVENTION),
+    }
+}
+
+
+/// Identify the environment variable and convention which determine a kind of
+///     configuration directory on the current platform.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn explain(_kind: DirKind) -> (Option<&'static str>, &'static str) {
+    if is_set("XDG_CONFIG_HOME", true) {
+        (Some("XDG_CONFIG_HOME"), "the XDG Base Directory specification")
+    } else if is_set("HOME", false) {
+        (
+            Some("HOME"),
+            "the XDG Base Directory default of ~/.config, since \
+            $XDG_CONFIG_HOME is not set to an absolute path",
+        )
+    } else {
+        (None, "the home directory given by the user database")
+    }
+}
@@ -0,0 +1,40 @@
+use std::io::ErrorKind;
+use serde::Serialize;
+use similar::TextDiff;
+use crate::{
+    config::{ConfigData, ConfigFile, ConfigSaveError},
+    format::ConfigFormat,
+    options::SaveOptions,
+    storage::ConfigStorage,
+};
+
+
+impl<Cfg, S: ConfigStorage> ConfigFile<Cfg, S> {
+    /// Produce a unified diff showing the changes that would be made to the
+    ///     file by saving the configuration in its current state.
+    ///
+    /// The old side of the diff is labelled `saved`, and the new side is
+    ///     labelled `unsaved`. If the file does not exist, the diff is against
+    ///     an empty file. If there are no changes, the result is empty.
+    ///
+    /// This requires the `diff` feature.
+    pub fn diff<F: ConfigFormat>(&self) -> Result<String, ConfigSaveError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        let saved = match self.path.read() {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let unsaved = self.data.to_text().map_err(|e| {
+            ConfigSaveError::SerializeFailure(F::explain_error(&self.data, e))
+        })?;
+        let unsaved = SaveOptions::default().normalize(unsaved);
+
+        Ok(TextDiff::from_lines(&saved, &unsaved)
+            .unified_diff()
+            .header("saved", "unsaved")
+            .to_string())
+    }
+}
@@ -0,0 +1,165 @@
+use std::io::ErrorKind;
+use serde::Serialize;
+use toml_edit::{DocumentMut, Item};
+use crate::{
+    config::{ConfigData, ConfigFile, ConfigSaveError, reject_dir},
+    format::{ConfigFormat, Toml},
+    options::SaveOptions,
+    storage::ConfigStorage,
+};
+
+
+/// Set the document position of every table within an item, so that they are
+///     rendered together, in order, at that position.
+fn set_position(item: &mut Item, position: usize) {
+    let tables: Vec<&mut toml_edit::Table> = match item {
+        Item::Table(table) => vec![table],
+        Item::ArrayOfTables(array) => array.iter_mut().collect(),
+        _ => return,
+    };
+
+    for table in tables {
+        table.set_position(position);
+
+        for (_, child) in table.iter_mut() {
+            set_position(child, position);
+        }
+    }
+}
+
+
+/// Find the highest document position of any table within an item.
+fn max_position(item: &Item) -> Option<usize> {
+    let tables: Vec<&toml_edit::Table> = match item {
+        Item::Table(table) => vec![table],
+        Item::ArrayOfTables(array) => array.iter().collect(),
+        _ => return None,
+    };
+
+    tables.into_iter().flat_map(|table| {
+        table.iter()
+            .filter_map(|(_, child)| max_position(child))
+            .chain(table.position())
+    }).max()
+}
+
+
+/// Update an item in place to match a new item, changing as little as possible
+///     so that comments and formatting are preserved.
+fn merge(old: &mut Item, new: Item) {
+    match (old, new) {
+        (Item::Table(prev), Item::Table(next)) => {
+            prev.retain(|key, _| next.contains_key(key));
+
+            for (key, mut item) in next {
+                match prev.get_mut(&key) {
+                    Some(slot) => merge(slot, item),
+                    None => {
+                        if let Some(position) = prev.position() {
+                            set_position(&mut item, position);
+                        }
+
+                        prev.insert(&key, item);
+                    }
+                }
+            }
+        }
+        (Item::Value(prev), Item::Value(next)) => {
+            let decor = prev.decor().clone();
+            *prev = next;
+            *prev.decor_mut() = decor;
+        }
+        (slot, next) => *slot = next,
+    }
+}
+
+
+impl<Cfg> ConfigFile<Cfg> {
+    /// Write a single top-level key of the configuration into the file at the
+    ///     associated path, leaving the rest of the file untouched.
+    ///
+    /// Comments and formatting outside of the named key are preserved exactly.
+    ///     Within the key, values are updated in place where possible, so that
+    ///     comments attached to existing entries are kept as well. If the key
+    ///     does not yet exist in the file, it is added; If the key is not
+    ///     present in the serialized configuration, it is removed from the
+    ///     file. If the file does not exist, it is created, containing only the
+    ///     named key. The file is written using the default [`SaveOptions`].
+    ///
+    /// This is only available for configurations stored as [`Toml`], and
+    ///     requires the `edit` feature.
+    pub fn save_section(&self, key: &str) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<Toml> + Serialize
+    {
+        self.save_section_with(key, &SaveOptions::default())
+    }
+
+    /// Write a single top-level key of the configuration into the file at the
+    ///     associated path, as with [`save_section`], with explicit options.
+    ///
+    /// The file is written as by [`save_with`], so that backups, atomic writes
+    ///     and compression apply here as well. Options which control how the
+    ///     configuration is serialized apply only to the named key.
+    ///
+    /// [`save_section`]: Self::save_section
+    /// [`save_with`]: Self::save_with
+    pub fn save_section_with(
+        &self,
+        key: &str,
+        opts: &SaveOptions,
+    ) -> Result<(), ConfigSaveError>
+        where Cfg: ConfigData<Toml> + Serialize
+    {
+        reject_dir(&self.path)?;
+
+        let text = match self.path.read() {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut doc: DocumentMut = text.parse()
+            .map_err(|e| ConfigSaveError::FileInvalid(Box::new(e)))?;
+
+        let mut data = match toml::Value::try_from(&self.data)? {
+            toml::Value::Table(table) => table,
+            _ => return Err(ConfigSaveError::SerializeFailure(
+                "Configuration is not a table.".into(),
+            )),
+        };
+
+        match data.remove(key) {
+            None => { doc.remove(key); }
+            Some(value) => {
+                let mut wrap = toml::value::Table::new();
+                wrap.insert(key.to_owned(), value);
+
+                let mut new: DocumentMut = Toml::to_string_with(&wrap, opts)?
+                    .parse()
+                    .map_err(|e| ConfigSaveError::SerializeFailure(Box::new(e)))?;
+                let mut item = new.remove(key).unwrap_or_default();
+
+                match doc.get_mut(key) {
+                    Some(old) => merge(old, item),
+                    None => {
+                        let position = max_position(doc.as_item())
+                            .map_or(0, |p| p + 1);
+
+                        set_position(&mut item, position);
+
+                        if let Item::Table(table) = &mut item {
+                            if !text.trim().is_empty() {
+                                table.decor_mut().set_prefix("\n");
+                            }
+                        }
+
+                        doc.insert(key, item);
+                    }
+                }
+            }
+        }
+
+        let text = opts.normalize(doc.to_string());
+        Ok(self.path.write(&text, opts)?)
+    }
+}
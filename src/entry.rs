@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::{
+    config::{ConfigData, ConfigError, ConfigFile, ConfigOpen},
+    format::ConfigFormat,
+    options::SaveOptions,
+};
+
+
+/// A configuration file which may or may not exist, returned by
+///     [`ConfigData::entry`], in the manner of the `Entry` of a [`HashMap`].
+///
+/// Either variant may be completed into a [`ConfigFile`] by one of the `or_*`
+///     methods, which create a new file only if the entry is [`Vacant`], and
+///     otherwise return the existing file. The variant shows whether the file
+///     had to be created.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[derive(serde::Deserialize)] struct Config { number: u32 }
+/// use tomlconf::{ConfigData, ConfigEntry};
+///
+/// impl ConfigData for Config {
+///     const DEFAULT: &'static str = "number = 3";
+/// }
+///
+/// let entry = Config::entry("config.toml");
+///
+/// if let ConfigEntry::Vacant(path) = &entry {
+///     eprintln!("Creating new Config file: {}", path.display());
+/// }
+///
+/// let file = entry.or_create_default().unwrap();
+/// println!("{}", file.number);
+/// ```
+///
+/// [`HashMap`]: std::collections::HashMap
+/// [`Vacant`]: Self::Vacant
+pub enum ConfigEntry<Cfg> {
+    /// A file exists at this path; Also includes the result of attempting to
+    ///     load it. If it could not be determined whether the file exists,
+    ///     this is the variant used, holding the error, so that a file which
+    ///     may exist is never replaced.
+    Occupied(PathBuf, ConfigOpen<Cfg>),
+    /// No file exists at this path.
+    Vacant(PathBuf),
+}
+
+impl<Cfg> ConfigEntry<Cfg> {
+    /// Return a reference to the path of the file.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Occupied(path, _) | Self::Vacant(path) => path,
+        }
+    }
+
+    /// Return `true` if no file exists, so that one of the `or_*` methods will
+    ///     create a new one.
+    pub fn is_vacant(&self) -> bool { matches!(self, Self::Vacant(..)) }
+
+    /// Return the existing file, or create a new default file, as with
+    ///     [`ConfigData::create`], and return that instead.
+    pub fn or_create_default<F: ConfigFormat>(
+        self,
+    ) -> Result<ConfigFile<Cfg>, ConfigError>
+        where Cfg: ConfigData<F>
+    {
+        match self {
+            Self::Occupied(path, open) => occupied(path, open),
+            Self::Vacant(path) => {
+                Cfg::create(&path, true, true)
+                    .map_err(ConfigError::FileInaccessible)?;
+                let open = Cfg::open(&path);
+                occupied(path, open)
+            }
+        }
+    }
+
+    /// Return the existing file, or save the configuration returned by a
+    ///     function as a new file, and return that instead. The function is
+    ///     only called if the entry is vacant.
+    pub fn or_insert_with<F: ConfigFormat>(
+        self,
+        default: impl FnOnce() -> Cfg,
+    ) -> Result<ConfigFile<Cfg>, ConfigError>
+        where Cfg: ConfigData<F> + Serialize
+    {
+        match self {
+            Self::Occupied(path, open) => occupied(path, open),
+            Self::Vacant(path) => {
+                let file = default().with_path(path);
+                file.save_with(&SaveOptions::default())?;
+                Ok(file)
+            }
+        }
+    }
+}
+
+
+/// Complete an occupied entry with the result of opening its file.
+fn occupied<Cfg>(
+    path: PathBuf,
+    open: ConfigOpen<Cfg>,
+) -> Result<ConfigFile<Cfg>, ConfigError> {
+    Ok(ConfigFile::new(open.into_config_result()?, path))
+}
@@ -0,0 +1,156 @@
+use std::{env::{var, VarError}, fmt::{Display, Formatter, self}, str::FromStr};
+use crate::format::FormatError;
+
+
+/// A function which parses a value and stores it into a field.
+type Setter<Cfg> = dyn Fn(&mut Cfg, &str) -> Result<(), FormatError>;
+
+
+/// A binding between one field of a configuration and a specific environment
+///     variable, which overrides the value of that field when it is set.
+///
+/// Bindings are declared by [`ConfigData::env_bindings`], and are applied when
+///     a configuration is opened, after it is parsed and before it is passed
+///     through [`ConfigData::prepare`]. Variables which are not set are
+///     ignored.
+///
+/// # Example
+///
+/// ```
+/// # #[derive(serde::Deserialize)] struct Database { url: String }
+/// # #[derive(serde::Deserialize)] struct Config { database: Database, port: u16 }
+/// use tomlconf::{ConfigData, EnvBinding};
+///
+/// impl ConfigData for Config {
+///     const DEFAULT: &'static str = "port = 8080\n[database]\nurl = ''";
+///
+///     fn env_bindings() -> Vec<EnvBinding<Self>> {
+///         vec![
+///             EnvBinding::new(
+///                 "DATABASE_URL", "database.url",
+///                 |cfg: &mut Self| &mut cfg.database.url,
+///             ),
+///             EnvBinding::new("PORT", "port", |cfg: &mut Self| &mut cfg.port),
+///         ]
+///     }
+/// }
+/// ```
+///
+/// [`ConfigData::env_bindings`]: crate::ConfigData::env_bindings
+/// [`ConfigData::prepare`]: crate::ConfigData::prepare
+pub struct EnvBinding<Cfg> {
+    var: &'static str,
+    field: &'static str,
+    apply: Box<Setter<Cfg>>,
+}
+
+impl<Cfg: 'static> EnvBinding<Cfg> {
+    /// Bind a field to an environment variable. The value of the variable is
+    ///     parsed by the [`FromStr`] implementation of the field type.
+    ///
+    /// # Arguments
+    ///
+    /// * `var`: The name of the environment variable.
+    /// * `field`: The name of the field, used only to describe errors.
+    /// * `access`: A function returning a mutable reference to the field.
+    pub fn new<T>(
+        var: &'static str,
+        field: &'static str,
+        access: fn(&mut Cfg) -> &mut T,
+    ) -> Self where
+        T: FromStr + 'static,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            var,
+            field,
+            apply: Box::new(move |cfg, value| {
+                *access(cfg) = value.parse()?;
+                Ok(())
+            }),
+        }
+    }
+
+    /// Bind an optional field to an environment variable, as with [`new`].
+    ///     When the variable is set, the field is set to [`Some`].
+    ///
+    /// [`new`]: Self::new
+    pub fn optional<T>(
+        var: &'static str,
+        field: &'static str,
+        access: fn(&mut Cfg) -> &mut Option<T>,
+    ) -> Self where
+        T: FromStr + 'static,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            var,
+            field,
+            apply: Box::new(move |cfg, value| {
+                *access(cfg) = Some(value.parse()?);
+                Ok(())
+            }),
+        }
+    }
+}
+
+impl<Cfg> EnvBinding<Cfg> {
+    /// Return the name of the environment variable.
+    pub fn var(&self) -> &'static str { self.var }
+
+    /// Return the name of the field.
+    pub fn field(&self) -> &'static str { self.field }
+
+    /// Override the field of a configuration with the value of the environment
+    ///     variable, if it is set.
+    pub fn apply(&self, cfg: &mut Cfg) -> Result<(), EnvError> {
+        let error = match var(self.var) {
+            Err(VarError::NotPresent) => { return Ok(()); }
+            Err(e) => e.into(),
+            Ok(value) => match (self.apply)(cfg, &value) {
+                Ok(()) => { return Ok(()); }
+                Err(e) => e,
+            }
+        };
+
+        Err(EnvError { var: self.var, field: self.field, error })
+    }
+}
+
+
+/// An error produced when the value of an environment variable bound to a
+///     field by an [`EnvBinding`] could not be parsed.
+#[derive(Debug)]
+pub struct EnvError {
+    var: &'static str,
+    field: &'static str,
+    error: FormatError,
+}
+
+impl EnvError {
+    /// Return the name of the environment variable.
+    pub fn var(&self) -> &'static str { self.var }
+
+    /// Return the name of the field.
+    pub fn field(&self) -> &'static str { self.field }
+
+    /// Return a reference to the error produced while parsing the value.
+    pub fn error(&self) -> &FormatError { &self.error }
+}
+
+
+impl Display for EnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "Invalid value for field `{}` in environment variable {}: {}",
+            self.field, self.var, self.error,
+        )
+    }
+}
+
+
+impl std::error::Error for EnvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.error)
+    }
+}
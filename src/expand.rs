@@ -0,0 +1,206 @@
+use std::{
+    env::var_os,
+    ffi::OsString,
+    fmt::{Display, Formatter, self},
+    path::{Path, PathBuf},
+};
+
+
+/// An error returned when a path cannot be expanded.
+#[derive(Debug)]
+pub enum ExpandError {
+    /// The path begins with `~`, but the home directory could not be found.
+    NoHome,
+    /// The path refers to an environment variable which is not set.
+    UndefinedVar(String),
+}
+
+
+impl Display for ExpandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoHome => f.write_str("Cannot find home directory."),
+            Self::UndefinedVar(name) => {
+                write!(f, "Environment variable is not set: {}", name)
+            }
+        }
+    }
+}
+
+
+impl std::error::Error for ExpandError {}
+
+
+/// Find the home directory of the current user.
+fn home_dir() -> Option<OsString> {
+    #[cfg(windows)]
+    let home = var_os("USERPROFILE");
+    #[cfg(not(windows))]
+    let home = var_os("HOME");
+
+    home.filter(|h| !h.is_empty())
+}
+
+
+/// Look up the value of an environment variable by name.
+fn lookup(name: &str) -> Result<OsString, ExpandError> {
+    var_os(name).ok_or_else(|| ExpandError::UndefinedVar(name.to_owned()))
+}
+
+
+/// Return the length of the variable name at the start of a string.
+fn name_len(s: &str) -> usize {
+    match s.chars().next() {
+        Some(c) if c == '_' || c.is_ascii_alphabetic() => s
+            .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+            .unwrap_or(s.len()),
+        _ => 0,
+    }
+}
+
+
+/// Expand a leading `~` and any environment variable references in a path,
+///     such as one given by the user on the command line.
+///
+/// A `~` is only expanded when it is the entire path, or is followed directly
+///     by a path separator. Variables may be written as `$NAME` or `${NAME}`;
+///     On Windows, the `%NAME%` syntax is also accepted. A `$` or `%` which
+///     does not begin a variable reference is kept as it is.
+///
+/// Expansion is never performed automatically; Paths passed directly to other
+///     functions in this crate are always used literally.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use tomlconf::expand_path;
+///
+/// std::env::set_var("APP_DIR", "/opt/app");
+///
+/// assert_eq!(
+///     expand_path("$APP_DIR/config.toml").unwrap(),
+///     PathBuf::from("/opt/app/config.toml"),
+/// );
+/// assert_eq!(
+///     expand_path("${APP_DIR}_old/$5.toml").unwrap(),
+///     PathBuf::from("/opt/app_old/$5.toml"),
+/// );
+/// assert!(expand_path("$NOT_A_REAL_VARIABLE/config.toml").is_err());
+/// ```
+pub fn expand_path(path: &str) -> Result<PathBuf, ExpandError> {
+    let mut out = OsString::with_capacity(path.len());
+    let mut rest = path;
+
+    if let Some(tail) = path.strip_prefix('~') {
+        if tail.is_empty() || tail.starts_with(std::path::is_separator) {
+            out.push(home_dir().ok_or(ExpandError::NoHome)?);
+            rest = tail;
+        }
+    }
+
+    while let Some(idx) = rest.find(|c| c == '$' || (cfg!(windows) && c == '%')) {
+        out.push(&rest[..idx]);
+        let tail = &rest[idx + 1..];
+
+        if rest[idx..].starts_with('%') {
+            match tail.find('%') {
+                Some(end) if end > 0 && name_len(tail) == end => {
+                    out.push(lookup(&tail[..end])?);
+                    rest = &tail[end + 1..];
+                }
+                _ => {
+                    out.push("%");
+                    rest = tail;
+                }
+            }
+        } else if let Some(braced) = tail.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) if end > 0 && name_len(braced) == end => {
+                    out.push(lookup(&braced[..end])?);
+                    rest = &braced[end + 1..];
+                }
+                _ => {
+                    out.push("$");
+                    rest = tail;
+                }
+            }
+        } else {
+            let len = name_len(tail);
+
+            match len {
+                0 => out.push("$"),
+                _ => out.push(lookup(&tail[..len])?),
+            }
+
+            rest = &tail[len..];
+        }
+    }
+
+    out.push(rest);
+    Ok(out.into())
+}
+
+
+/// Anchor a relative path at a base directory, such as the directory containing
+///     a configuration file. An absolute path is returned unchanged.
+///
+/// This is intended to be used in [`ConfigData::prepare_with_path`], so that a
+///     configuration may refer to adjacent files by paths relative to itself,
+///     rather than to the current working directory.
+///
+/// [`ConfigData::prepare_with_path`]: crate::ConfigData::prepare_with_path
+///
+/// # Example
+///
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use tomlconf::resolve_relative;
+///
+/// let file = Path::new("/etc/app/config.toml");
+/// let base = file.parent().unwrap();
+///
+/// assert_eq!(
+///     resolve_relative(base, Path::new("certs/app.pem")),
+///     PathBuf::from("/etc/app/certs/app.pem"),
+/// );
+///
+/// let absolute = std::env::temp_dir().join("app.pem");
+/// assert_eq!(resolve_relative(base, &absolute), absolute);
+/// ```
+pub fn resolve_relative(base: &Path, path: &Path) -> PathBuf {
+    base.join(path)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(windows)]
+    fn expand_windows_vars() {
+        std::env::set_var("TOMLCONF_TEST_WIN", r"C:\App");
+
+        assert_eq!(
+            expand_path(r"%TOMLCONF_TEST_WIN%\config.toml").unwrap(),
+            PathBuf::from(r"C:\App\config.toml"),
+        );
+        assert_eq!(
+            expand_path(r"50%\%TOMLCONF_TEST_WIN%").unwrap(),
+            PathBuf::from(r"50%\C:\App"),
+        );
+        assert!(expand_path("%TOMLCONF_TEST_NOT_SET%").is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn expand_keeps_percent() {
+        std::env::set_var("TOMLCONF_TEST_UNIX", "/opt/app");
+
+        assert_eq!(
+            expand_path("%TOMLCONF_TEST_UNIX%/$TOMLCONF_TEST_UNIX").unwrap(),
+            PathBuf::from("%TOMLCONF_TEST_UNIX%//opt/app"),
+        );
+    }
+}
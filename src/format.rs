@@ -0,0 +1,453 @@
+use std::{fmt::{Display, Formatter, self}, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::options::SaveOptions;
+#[cfg(feature = "fs")]
+use std::{io::Read, path::Path};
+#[cfg(feature = "fs")]
+use crate::config::ConfigOpen;
+
+
+/// A boxed error produced by a [`ConfigFormat`] while reading or writing data.
+pub type FormatError = Box<dyn std::error::Error + Send + Sync>;
+
+
+/// A data format in which a configuration may be stored.
+///
+/// This crate provides [`Toml`], which is used by default, as well as other
+///     formats behind optional features. Implement this trait for a new type
+///     in order to store configurations in any other format supported by
+///     [`serde`].
+pub trait ConfigFormat {
+    /// The error returned when text cannot be parsed into a configuration.
+    type DeError: std::error::Error + Send + Sync + 'static;
+    /// The error returned when a configuration cannot be converted into text.
+    type SerError: std::error::Error + Send + Sync + 'static;
+
+    /// Parse a configuration from text.
+    ///
+    /// The result may borrow from the text, if the type supports it.
+    fn from_str<'de, T: Deserialize<'de>>(s: &'de str) -> Result<T, Self::DeError>;
+
+    /// Parse a configuration from text, as with [`from_str`], and also return
+    ///     the path of each key in the text which was ignored because the type
+    ///     does not declare it.
+    ///
+    /// By default, this calls [`from_str`] and reports no keys.
+    ///
+    /// [`from_str`]: Self::from_str
+    fn from_str_tracked<'de, T: Deserialize<'de>>(
+        s: &'de str,
+    ) -> Result<(T, Vec<String>), Self::DeError> {
+        Self::from_str(s).map(|value| (value, Vec::new()))
+    }
+
+    /// Convert a configuration into text.
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Self::SerError>;
+
+    /// Convert a configuration into text, as it will be saved with the given
+    ///     options. By default, this ignores the options and calls
+    ///     [`to_string`].
+    ///
+    /// [`to_string`]: Self::to_string
+    fn to_string_with<T: Serialize + ?Sized>(
+        value: &T,
+        _opts: &SaveOptions,
+    ) -> Result<String, Self::SerError> {
+        Self::to_string(value)
+    }
+
+    /// Find the position in the source text at which a parse error occurred,
+    ///     as a pair of line and column numbers, both starting from 1.
+    ///
+    /// This returns [`None`] by default, and should be overridden by formats
+    ///     whose errors carry location information.
+    fn error_position(_error: &Self::DeError) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Describe an error produced while converting a value into text, adding
+    ///     as much context as possible, such as the field which could not be
+    ///     converted.
+    ///
+    /// By default, the error is returned as it is.
+    fn explain_error<T: Serialize + ?Sized>(
+        _value: &T,
+        error: Self::SerError,
+    ) -> FormatError {
+        error.into()
+    }
+
+    /// Read the file at the given path into a buffer, and parse a value which
+    ///     borrows from that buffer, avoiding copies of any string data.
+    ///
+    /// This is an alternative to [`ConfigData::open`] for types which are not
+    ///     [`DeserializeOwned`], and is mainly useful for large configurations
+    ///     which are read often. Because the returned value borrows from the
+    ///     buffer, the buffer cannot be reused for the next read until that
+    ///     value has been dropped; Its allocation is kept, however, so repeated
+    ///     reads do not need to allocate again.
+    ///
+    /// A compressed file is decompressed, as it is by [`ConfigData::open`].
+    ///     Since the type need not implement [`ConfigData`], however, the rest
+    ///     of the pipeline of [`ConfigData::open`] is skipped: An empty file
+    ///     always fails with [`ConfigOpen::FileEmpty`], rather than being
+    ///     replaced by a default, no environment variables are applied, and
+    ///     the result is not passed through [`ConfigData::prepare`]. Nor is it
+    ///     checked by [`ConfigData::validate`], as it would be by
+    ///     [`ConfigData::load`].
+    ///
+    /// [`ConfigData`]: crate::ConfigData
+    /// [`ConfigData::load`]: crate::ConfigData::load
+    /// [`ConfigData::open`]: crate::ConfigData::open
+    /// [`ConfigData::prepare`]: crate::ConfigData::prepare
+    /// [`ConfigData::validate`]: crate::ConfigData::validate
+    /// [`DeserializeOwned`]: serde::de::DeserializeOwned
+    #[cfg(feature = "fs")]
+    fn open_borrowed<'de, T: Deserialize<'de>>(
+        path: &Path,
+        buf: &'de mut String,
+    ) -> ConfigOpen<T> {
+        buf.clear();
+
+        #[cfg(feature = "gzip")]
+        let read = match crate::gzip::is_compressed(path) {
+            true => crate::gzip::read_into(path, buf),
+            false => std::fs::File::open(path)
+                .and_then(|mut file| file.read_to_string(buf)),
+        };
+
+        #[cfg(not(feature = "gzip"))]
+        let read = std::fs::File::open(path)
+            .and_then(|mut file| file.read_to_string(buf));
+
+        if let Err(e) = read {
+            return ConfigOpen::FileInaccessible(e);
+        }
+
+        if buf.trim().is_empty() {
+            return ConfigOpen::FileEmpty;
+        }
+
+        let text: &'de str = buf;
+
+        match Self::from_str(text) {
+            Err(e) => ConfigOpen::FileInvalid(
+                ParseError::new::<Self>(e, text.to_owned()),
+            ),
+            Ok(value) => ConfigOpen::FileValid(value),
+        }
+    }
+}
+
+
+/// An error produced when the text of a configuration could not be parsed.
+///
+/// The full source text is retained alongside the error, so that it may be
+///     shown to the user or passed on to more elaborate diagnostic tools. If
+///     the format is able to report where the error occurred, the position is
+///     also retained, and the offending line is shown when the error is
+///     displayed.
+///
+/// When a configuration is assembled from several files, the path of the file
+///     which could not be parsed may also be attached.
+#[derive(Debug)]
+pub struct ParseError {
+    error: FormatError,
+    position: Option<(usize, usize)>,
+    text: String,
+    context: Option<Box<Context>>,
+}
+
+
+/// Information about where a [`ParseError`] arose, kept behind a pointer so
+///     that the error remains small.
+#[derive(Debug, Default)]
+struct Context {
+    path: Option<PathBuf>,
+    note: Option<String>,
+}
+
+impl ParseError {
+    /// Wrap an error produced by a format while parsing the given text.
+    pub fn new<F: ConfigFormat + ?Sized>(error: F::DeError, text: String) -> Self {
+        let position = F::error_position(&error);
+        Self { error: error.into(), position, text, context: None }
+    }
+
+    /// Wrap an error whose source text is no longer available.
+    pub(crate) fn from_error(error: FormatError) -> Self {
+        Self {
+            error,
+            position: None,
+            text: String::new(),
+            context: None,
+        }
+    }
+
+    /// Attach the path of the file which could not be parsed.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let context = self.context.get_or_insert_with(Default::default);
+        context.path = Some(path.into());
+        self
+    }
+
+    /// Attach a note giving further context, shown after the rest of the error.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        let context = self.context.get_or_insert_with(Default::default);
+        context.note = Some(note.into());
+        self
+    }
+
+    /// Return a reference to the error produced by the format.
+    pub fn error(&self) -> &FormatError { &self.error }
+
+    /// Return the error produced by the format.
+    pub fn into_error(self) -> FormatError { self.error }
+
+    /// Return the error produced by the format, along with the source text.
+    pub fn into_parts(self) -> (FormatError, String) { (self.error, self.text) }
+
+    /// Return the line and column at which the error occurred, if known.
+    pub fn position(&self) -> Option<(usize, usize)> { self.position }
+
+    /// Return the full line of text at which the error occurred, if known.
+    pub fn snippet(&self) -> Option<&str> {
+        let (line, _) = self.position?;
+        self.text.lines().nth(line.checked_sub(1)?)
+    }
+
+    /// Return the full source text which could not be parsed.
+    pub fn text(&self) -> &str { &self.text }
+
+    /// Return the path of the file which could not be parsed, if known.
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.context.as_ref()?.path.as_ref()
+    }
+
+    /// Return the note giving further context, if there is one.
+    pub fn note(&self) -> Option<&str> {
+        self.context.as_ref()?.note.as_deref()
+    }
+}
+
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if let Some((line, col)) = self.position {
+            let num = line.to_string();
+            let pad = " ".repeat(num.len());
+
+            match self.path() {
+                Some(path) => write!(
+                    f, "\n{} --> {}, line {}, column {}",
+                    pad, path.display(), line, col,
+                )?,
+                None => write!(
+                    f, "\n{} --> line {}, column {}",
+                    pad, line, col,
+                )?,
+            }
+
+            if let Some(snippet) = self.snippet() {
+                write!(f, "\n{} |", pad)?;
+                write!(f, "\n{} | {}", num, snippet)?;
+                write!(f, "\n{} | {:>2$}", pad, "^", col)?;
+            }
+        } else if let Some(path) = self.path() {
+            write!(f, "\n --> {}", path.display())?;
+        }
+
+        if let Some(note) = self.note() {
+            write!(f, "\n = note: {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+
+/// An error produced when a configuration could not be converted into text,
+///     along with the field at which it occurred and an explanation of the
+///     limitation of the format, where these are known.
+#[derive(Debug)]
+pub struct SerializeError {
+    error: FormatError,
+    field: Option<String>,
+    hint: Option<&'static str>,
+}
+
+impl SerializeError {
+    /// Return a reference to the error produced by the format.
+    pub fn error(&self) -> &FormatError { &self.error }
+
+    /// Return the path of the field which could not be converted, if known.
+    pub fn field(&self) -> Option<&str> { self.field.as_deref() }
+
+    /// Return an explanation of the limitation of the format, if known.
+    pub fn hint(&self) -> Option<&'static str> { self.hint }
+}
+
+
+impl Display for SerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{} (at field `{}`)", self.error, field)?,
+            None => write!(f, "{}", self.error)?,
+        }
+
+        match self.hint {
+            Some(hint) => write!(f, "; {}", hint),
+            None => Ok(()),
+        }
+    }
+}
+
+
+impl std::error::Error for SerializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+
+/// The TOML format, as implemented by the [`toml`] crate.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Toml;
+
+impl ConfigFormat for Toml {
+    type DeError = toml::de::Error;
+    type SerError = toml::ser::Error;
+
+    fn from_str<'de, T: Deserialize<'de>>(s: &'de str) -> Result<T, Self::DeError> {
+        toml::from_str(s)
+    }
+
+    fn from_str_tracked<'de, T: Deserialize<'de>>(
+        s: &'de str,
+    ) -> Result<(T, Vec<String>), Self::DeError> {
+        let mut keys = Vec::new();
+
+        let value = serde_ignored::deserialize(
+            &mut toml::Deserializer::new(s),
+            |path| keys.push(path.to_string()),
+        )?;
+
+        Ok((value, keys))
+    }
+
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Self::SerError> {
+        toml::to_string(value)
+    }
+
+    fn to_string_with<T: Serialize + ?Sized>(
+        value: &T,
+        opts: &SaveOptions,
+    ) -> Result<String, Self::SerError> {
+        let style = &opts.toml;
+
+        if !style.pretty {
+            return Self::to_string(value);
+        }
+
+        let mut out = String::new();
+        let mut ser = toml::Serializer::pretty(&mut out);
+
+        ser.pretty_array_indent(style.array_indent)
+            .pretty_array_trailing_comma(style.array_trailing_comma)
+            .pretty_string(style.multiline_strings);
+
+        value.serialize(&mut ser)?;
+        Ok(out)
+    }
+
+    fn error_position(error: &Self::DeError) -> Option<(usize, usize)> {
+        error.line_col().map(|(line, col)| (line + 1, col + 1))
+    }
+
+    fn explain_error<T: Serialize + ?Sized>(
+        value: &T,
+        error: Self::SerError,
+    ) -> FormatError {
+        use toml::ser::Error::*;
+
+        let hint = match error {
+            UnsupportedNone => Some(
+                "TOML has no null value, so a field which is `None` can only \
+                be omitted from a table, and cannot be placed in an array",
+            ),
+            UnsupportedType => Some(
+                "TOML cannot represent this type, such as a unit value or an \
+                enum variant holding data",
+            ),
+            KeyNotString => Some(
+                "TOML keys must be strings, so a map with keys of any other \
+                type cannot be converted",
+            ),
+            ValueAfterTable => Some(
+                "TOML requires plain values to come before tables, so fields \
+                holding tables should be declared after all other fields",
+            ),
+            _ => None,
+        };
+
+        //  Convert the value again, to find the path of the offending field.
+        let mut buf = String::new();
+        let mut ser = toml::Serializer::new(&mut buf);
+
+        let field = serde_path_to_error::serialize(value, &mut ser).err()
+            .map(|e| e.path().to_string())
+            .filter(|path| path != ".");
+
+        Box::new(SerializeError { error: error.into(), field, hint })
+    }
+}
+
+
+/// The JSON format, as implemented by the [`serde_json`] crate.
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl ConfigFormat for Json {
+    type DeError = serde_json::Error;
+    type SerError = serde_json::Error;
+
+    fn from_str<'de, T: Deserialize<'de>>(s: &'de str) -> Result<T, Self::DeError> {
+        serde_json::from_str(s)
+    }
+
+    fn from_str_tracked<'de, T: Deserialize<'de>>(
+        s: &'de str,
+    ) -> Result<(T, Vec<String>), Self::DeError> {
+        let mut de = serde_json::Deserializer::from_str(s);
+        let mut keys = Vec::new();
+
+        let value = serde_ignored::deserialize(&mut de, |path| {
+            keys.push(path.to_string());
+        })?;
+
+        de.end()?;
+        Ok((value, keys))
+    }
+
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Self::SerError> {
+        serde_json::to_string_pretty(value)
+    }
+
+    fn error_position(error: &Self::DeError) -> Option<(usize, usize)> {
+        match error.line() {
+            0 => None,
+            line => Some((line, error.column().max(1))),
+        }
+    }
+}
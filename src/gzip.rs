@@ -0,0 +1,47 @@
+use std::{fs::File, io::{Read, Write}, path::Path};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+
+/// Return whether the file at a path is expected to be compressed, which is
+///     the case if its name ends in `.gz`.
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+
+/// Read and decompress the text of a compressed file.
+pub(crate) fn read(path: &Path) -> Result<String, std::io::Error> {
+    let mut buf = String::new();
+    read_into(path, &mut buf)?;
+
+    Ok(buf)
+}
+
+
+/// Read and decompress the text of a compressed file, appending it to a
+///     buffer.
+pub(crate) fn read_into(
+    path: &Path,
+    buf: &mut String,
+) -> Result<usize, std::io::Error> {
+    GzDecoder::new(File::open(path)?).read_to_string(buf)
+}
+
+
+/// Decompress the text held in the bytes of a compressed file.
+#[cfg(feature = "checksum")]
+pub(crate) fn decompress(bytes: &[u8]) -> Result<String, std::io::Error> {
+    let mut buf = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut buf)?;
+
+    Ok(buf)
+}
+
+
+/// Compress text, to be written into a compressed file.
+pub(crate) fn compress(text: &str) -> Result<Vec<u8>, std::io::Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(text.as_bytes())?;
+
+    encoder.finish()
+}
@@ -0,0 +1,83 @@
+//! Serde helpers for storing a [`Duration`] as a human-readable string, such as
+//!     `"30s"` or `"1h 15m"`, using the `humantime` crate.
+//!
+//! This module is intended to be used with the `with` attribute of Serde:
+//!
+//! ```
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//!
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Timeouts {
+//!     #[serde(with = "tomlconf::humantime")]
+//!     connect: Duration,
+//! }
+//!
+//!
+//! let parsed: Timeouts = toml::from_str(r#"connect = "1m 30s""#).unwrap();
+//! assert_eq!(parsed.connect, Duration::from_secs(90));
+//!
+//! let text = toml::to_string(&parsed).unwrap();
+//! assert_eq!(text.trim(), r#"connect = "1m 30s""#);
+//! ```
+
+use std::time::Duration;
+use serde::{de::{Error, Unexpected}, Deserialize, Deserializer, Serializer};
+
+
+/// Serialize a [`Duration`] as a human-readable string.
+pub fn serialize<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&::humantime::format_duration(*duration))
+}
+
+
+/// Deserialize a [`Duration`] from a human-readable string.
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+
+    ::humantime::parse_duration(&text).map_err(|_| D::Error::invalid_value(
+        Unexpected::Str(&text),
+        &"a duration, such as \"30s\" or \"1h 15m\"",
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Timeout {
+        #[serde(with = "super")]
+        timeout: Duration,
+    }
+
+    fn round_trip(duration: Duration, text: &str) {
+        let value = Timeout { timeout: duration };
+        let serial = toml::to_string(&value).unwrap();
+        assert_eq!(serial.trim(), format!("timeout = \"{}\"", text));
+
+        let parsed: Timeout = toml::from_str(&serial).unwrap();
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn durations_round_trip() {
+        round_trip(Duration::from_secs(4500), "1h 15m");
+        round_trip(Duration::ZERO, "0s");
+        round_trip(Duration::from_millis(1500), "1s 500ms");
+    }
+
+    #[test]
+    fn invalid_duration_fails() {
+        let err = toml::from_str::<Timeout>(r#"timeout = "soon""#).unwrap_err();
+        assert!(err.to_string().contains("a duration"));
+    }
+}
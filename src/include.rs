@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+use toml::Value;
+use crate::{
+    config::{ConfigData, ConfigOpen},
+    expand::resolve_relative,
+    format::{FormatError, ParseError, Toml},
+    merge::merge,
+    storage::ConfigStorage,
+};
+
+
+/// The key under which a file lists the other files it includes.
+const KEY: &str = "include";
+
+
+/// Describe a chain of included files, starting from the outermost file.
+fn describe(chain: &[PathBuf]) -> String {
+    chain.iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+
+/// Read a file, along with every file it includes, and merge them together.
+///     Each included file is merged in the order it is listed, and the file
+///     itself is merged over all of them.
+fn load<Cfg>(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Value, ConfigOpen<Cfg>> {
+    let inaccessible = |e: std::io::Error, chain: &[PathBuf]| {
+        let context = match chain.is_empty() {
+            true => path.display().to_string(),
+            false => format!("{} -> {}", describe(chain), path.display()),
+        };
+
+        ConfigOpen::FileInaccessible(std::io::Error::new(
+            e.kind(),
+            format!("{}: {}", context, e),
+        ))
+    };
+
+    let invalid = |e: ParseError, chain: &[PathBuf]| {
+        let e = e.with_path(path);
+
+        ConfigOpen::FileInvalid(match chain.split_last() {
+            Some((_, outer)) if !outer.is_empty() => {
+                e.with_note(format!("included by {}", describe(outer)))
+            }
+            _ => e,
+        })
+    };
+
+    let real = path.canonicalize().map_err(|e| inaccessible(e, chain))?;
+
+    if chain.contains(&real) {
+        chain.push(real);
+
+        let e: FormatError = format!(
+            "Files include each other in a cycle: {}",
+            describe(chain),
+        ).into();
+
+        return Err(ConfigOpen::FileInvalid(ParseError::from_error(e)));
+    }
+
+    chain.push(real);
+
+    let text = path.read().map_err(|e| inaccessible(e, chain))?;
+    let mut value: Value = match toml::from_str(&text) {
+        Ok(value) => value,
+        Err(e) => return Err(invalid(ParseError::new::<Toml>(e, text), chain)),
+    };
+
+    let listed = value.as_table_mut().and_then(|table| table.remove(KEY));
+    let includes: Vec<String> = match listed {
+        None => Vec::new(),
+        Some(Value::String(file)) => vec![file],
+        Some(Value::Array(files)) if files.iter().all(Value::is_str) => files
+            .into_iter()
+            .filter_map(|file| file.as_str().map(str::to_owned))
+            .collect(),
+        Some(..) => {
+            let e: FormatError = format!(
+                "`{}` must be a string or an array of strings",
+                KEY,
+            ).into();
+
+            return Err(invalid(ParseError::from_error(e), chain));
+        }
+    };
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut merged = Value::Table(Default::default());
+
+    for file in includes {
+        let included = load(&resolve_relative(dir, Path::new(&file)), chain)?;
+        merge(&mut merged, included);
+    }
+
+    merge(&mut merged, value);
+    chain.pop();
+
+    Ok(merged)
+}
+
+
+/// Read a configuration from a file, along with every file it includes.
+pub(crate) fn open<Cfg: ConfigData<Toml>>(path: &Path) -> ConfigOpen<Cfg> {
+    let value = match load(path, &mut Vec::new()) {
+        Ok(value) => value,
+        Err(e) => return e,
+    };
+
+    match value.try_into::<Cfg>() {
+        Err(e) => ConfigOpen::FileInvalid(
+            ParseError::new::<Toml>(e, String::new()).with_path(path),
+        ),
+        Ok(config) => match config.apply_env() {
+            Err(e) => ConfigOpen::EnvInvalid(e),
+            Ok(config) => ConfigOpen::FileValid(config.prepare_with_path(path)),
+        },
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    io::ErrorKind,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+};
+use crate::{
+    config::{ConfigData, ConfigOpen},
+    format::{ParseError, Toml},
+    merge::merge,
+    storage::ConfigStorage,
+};
+
+
+/// Read every layer in order, merging each one over those before it, and then
+///     convert the result into a configuration. Also returns the text of each
+///     layer, or [`None`] for layers which do not exist.
+///
+/// The configuration is given the bindings of its environment variables, and
+///     is then prepared with the path of the last layer which exists, as the
+///     most specific, or without a path if none exist.
+fn load<Cfg: ConfigData<Toml>>(
+    layers: &[PathBuf],
+) -> Result<(Cfg, Vec<Option<String>>), ConfigOpen<Cfg>> {
+    let mut merged = toml::Value::Table(Default::default());
+    let mut sources = Vec::with_capacity(layers.len());
+    let mut last = None;
+
+    for path in layers {
+        match path.read() {
+            Ok(text) => {
+                match toml::from_str(&text) {
+                    Ok(value) => merge(&mut merged, value),
+                    Err(e) => return Err(ConfigOpen::FileInvalid(
+                        ParseError::new::<Toml>(e, text).with_path(path),
+                    )),
+                }
+
+                sources.push(Some(text));
+                last = Some(path);
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => sources.push(None),
+            Err(e) => return Err(ConfigOpen::FileInaccessible(
+                std::io::Error::new(
+                    e.kind(),
+                    format!("{}: {}", path.display(), e),
+                ),
+            )),
+        }
+    }
+
+    let config = match merged.try_into::<Cfg>() {
+        Ok(config) => config,
+        Err(e) => return Err(ConfigOpen::FileInvalid(
+            ParseError::new::<Toml>(e, String::new()),
+        )),
+    };
+
+    let config = match config.apply_env() {
+        Ok(config) => config,
+        Err(e) => return Err(ConfigOpen::EnvInvalid(e)),
+    };
+
+    match last {
+        Some(path) => Ok((config.prepare_with_path(path), sources)),
+        None => Ok((config.prepare(), sources)),
+    }
+}
+
+
+/// Read every `.toml` file in a directory, in order of their names, merging
+///     each one over those before it, and then convert the result into a
+///     configuration.
+pub(crate) fn load_dir<Cfg: ConfigData<Toml>>(dir: &Path) -> ConfigOpen<Cfg> {
+    let entries = match dir.read_dir() {
+        Ok(entries) => entries,
+        Err(e) => return ConfigOpen::FileInaccessible(e),
+    };
+
+    let mut fragments: Vec<PathBuf> = Vec::new();
+
+    for entry in entries {
+        match entry {
+            Ok(entry) => {
+                let path = entry.path();
+
+                if path.extension().is_some_and(|ext| ext == "toml")
+                    && path.is_file()
+                {
+                    fragments.push(path);
+                }
+            }
+            Err(e) => return ConfigOpen::FileInaccessible(e),
+        }
+    }
+
+    fragments.sort();
+
+    match load(&fragments) {
+        Ok((config, _)) => ConfigOpen::FileValid(config),
+        Err(e) => e,
+    }
+}
+
+
+/// A configuration assembled from several TOML files, or "layers", in order.
+///
+/// Each layer is merged over the layers before it: Tables are merged key by
+///     key, while any other value, including an array, replaces the value in
+///     earlier layers. Layers which do not exist are skipped, so that optional
+///     files, such as a system-wide file followed by a per-user file, may be
+///     listed freely.
+pub struct LayeredConfig<Cfg> {
+    /// The stored configuration state.
+    pub data: Cfg,
+    layers: Vec<PathBuf>,
+    sources: Vec<Option<String>>,
+}
+
+impl<Cfg: ConfigData<Toml>> LayeredConfig<Cfg> {
+    /// Read and merge all of the given layers, in order.
+    pub fn open<P: Into<PathBuf>>(
+        layers: impl IntoIterator<Item = P>,
+    ) -> Result<Self, ConfigOpen<Cfg>> {
+        let layers: Vec<PathBuf> = layers.into_iter().map(Into::into).collect();
+        let (data, sources) = load(&layers)?;
+
+        Ok(Self { data, layers, sources })
+    }
+
+    /// Read and merge all layers again, and replace the stored data.
+    ///
+    /// If successful, returns the indices of the layers whose contents have
+    ///     changed since they were last read, including layers which have been
+    ///     created or removed. If any layer fails, the stored data is left
+    ///     unchanged.
+    pub fn reload(&mut self) -> Result<Vec<usize>, ConfigOpen<Cfg>> {
+        let (data, sources) = load(&self.layers)?;
+        let changed = self.sources.iter()
+            .zip(&sources)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(idx, _)| idx)
+            .collect();
+
+        self.data = data;
+        self.sources = sources;
+
+        Ok(changed)
+    }
+}
+
+impl<Cfg> LayeredConfig<Cfg> {
+    /// Return the paths of all layers, in order.
+    pub fn layers(&self) -> &[PathBuf] { &self.layers }
+
+    /// Return the paths of the layers which existed when last read, in order.
+    pub fn present_layers(&self) -> impl Iterator<Item = &PathBuf> {
+        self.layers.iter()
+            .zip(&self.sources)
+            .filter(|(_, source)| source.is_some())
+            .map(|(path, _)| path)
+    }
+}
+
+
+impl<Cfg> Deref for LayeredConfig<Cfg> {
+    type Target = Cfg;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+
+impl<Cfg> DerefMut for LayeredConfig<Cfg> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use crate::{env::EnvBinding, testing::scratch};
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config { name: String, port: u16 }
+
+    impl ConfigData for Config {
+        const DEFAULT: &'static str = "name = ''\nport = 80";
+
+        fn env_bindings() -> Vec<EnvBinding<Self>> {
+            vec![EnvBinding::new(
+                "TOMLCONF_TEST_LAYERED_PORT", "port",
+                |cfg: &mut Self| &mut cfg.port,
+            )]
+        }
+    }
+
+    #[test]
+    fn layers_apply_env() {
+        let dir = scratch("layered-env");
+        std::fs::write(dir.join("a.toml"), "name = 'a'\nport = 80").unwrap();
+        std::fs::write(dir.join("b.toml"), "name = 'b'").unwrap();
+        std::env::set_var("TOMLCONF_TEST_LAYERED_PORT", "8080");
+
+        let layers = [dir.join("a.toml"), dir.join("b.toml")];
+        let layered = LayeredConfig::<Config>::open(layers).ok().unwrap();
+        assert_eq!((layered.name.as_str(), layered.port), ("b", 8080));
+
+        let merged = Config::load_dir(&dir).into_config().unwrap();
+        assert_eq!((merged.name.as_str(), merged.port), ("b", 8080));
+    }
+}
@@ -9,12 +9,23 @@
 //!     file; If you also derive [`Serialize`], you can even save changes to the
 //!     data back into the file.
 //!
+//! Files are stored as TOML by default. Other formats may be selected through
+//!     the [`ConfigFormat`] trait; JSON is available with the `json` feature.
+//!
+//! All interaction with the filesystem lives behind the `fs` feature, which is
+//!     enabled by default. Without it, configurations may still be parsed from
+//!     and converted into text, using [`ConfigData::from_text`] and
+//!     [`ConfigData::to_text`]. Locating files in the standard configuration
+//!     directory of the platform is further gated behind the `directories`
+//!     feature, also enabled by default; Without it, files may still be read
+//!     and written at explicit paths.
+//!
 //! [`DeserializeOwned`]: serde::de::DeserializeOwned
 //! [`Serialize`]: serde::Serialize
 //!
 //! # Example
 //!
-//! ```
+//! ```no_run
 //! use serde::Deserialize;
 //! use tomlconf::*;
 //!
@@ -26,7 +37,11 @@
 //! }
 //!
 //! impl ConfigData for AppConfig {
-//!     const DEFAULT: &'static str = include_str!("cfg_default.toml");
+//!     //  Typically `include_str!("cfg_default.toml")`.
+//!     const DEFAULT: &'static str = r#"
+//!         output = "Hello, world!"
+//!         number = 3
+//!     "#;
 //! }
 //!
 //!
@@ -55,9 +70,124 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Optional fields
+//!
+//! TOML has no way to write an empty value, so a field of type [`Option`] which
+//!     is [`None`] is left out when a configuration is saved as TOML, and a
+//!     key which is missing from the file is read as [`None`]. An [`Option`]
+//!     therefore survives a round trip through a file unchanged, whether or
+//!     not it is also marked `#[serde(default)]`, and the key reappears when
+//!     the field is set again. This applies equally to [`DEFAULT`], which may
+//!     simply leave out any such fields.
+//!
+//! Fields of any other type are always written out, even when they hold their
+//!     default value, so that the file shows every setting which is in effect.
+//!     A field may be left out when it holds a particular value by marking it
+//!     with `#[serde(skip_serializing_if = "...")]`; If it is also marked
+//!     `#[serde(default)]`, it will be read back with the same value.
+//!
+//! Other formats may behave differently; JSON, for instance, writes [`None`] as
+//!     an explicit `null`, but likewise reads a missing key as [`None`].
+//!
+//! [`DEFAULT`]: ConfigData::DEFAULT
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use tomlconf::ConfigData;
+//!
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Config {
+//!     name: String,
+//!     nickname: Option<String>,
+//!     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+//!     aliases: Vec<String>,
+//! }
+//!
+//! impl ConfigData for Config {
+//!     const DEFAULT: &'static str = r#"name = "Ferris""#;
+//! }
+//!
+//! let config = Config::from_text(Config::DEFAULT.into()).unwrap();
+//! assert_eq!(config.nickname, None);
+//!
+//! let text = config.to_text().unwrap();
+//! assert_eq!(text, "name = \"Ferris\"\n");
+//! assert_eq!(Config::from_text(text).unwrap(), config);
+//!
+//! let config = Config { nickname: Some("Crab".into()), ..config };
+//! let text = config.to_text().unwrap();
+//! assert!(text.contains("nickname = \"Crab\""));
+//! assert_eq!(Config::from_text(text).unwrap(), config);
+//! ```
 
 #![cfg_attr(feature = "nightly", feature(try_trait_v2))]
+#![allow(clippy::doc_overindented_list_items)]
 
+#[cfg(feature = "directories")]
+mod app;
+mod cache;
+mod changes;
+#[cfg(feature = "checksum")]
+mod checksum;
 mod config;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(feature = "diff")]
+mod diff;
+#[cfg(feature = "edit")]
+mod edit;
+#[cfg(feature = "fs")]
+mod entry;
+mod env;
+mod expand;
+mod format;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "fs")]
+mod include;
+#[cfg(feature = "fs")]
+mod layered;
+#[cfg(feature = "fs")]
+mod merge;
+#[cfg(feature = "directories")]
+mod names;
+mod options;
+#[cfg(all(feature = "tokio", unix))]
+mod sighup;
+mod storage;
+#[cfg(feature = "fs")]
+mod strategy;
+#[cfg(all(test, feature = "fs"))]
+mod testing;
+mod validate;
+#[cfg(feature = "fs")]
+mod version;
+mod warning;
+
+#[cfg(feature = "seconds")]
+pub mod as_seconds;
+#[cfg(feature = "humantime")]
+pub mod humantime;
 
+#[cfg(feature = "directories")]
+pub use app::*;
+pub use changes::*;
 pub use config::*;
+#[cfg(feature = "fs")]
+pub use entry::*;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::*;
+pub use env::*;
+pub use expand::*;
+pub use format::*;
+#[cfg(feature = "fs")]
+pub use layered::*;
+#[cfg(feature = "directories")]
+pub use names::*;
+pub use options::*;
+pub use storage::*;
+#[cfg(feature = "fs")]
+pub use strategy::*;
+pub use validate::*;
+pub use warning::*;
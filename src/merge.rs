@@ -0,0 +1,18 @@
+use toml::Value;
+
+
+/// Merge one value over another. Tables are merged recursively, key by key;
+///     Any other value, including an array, replaces the value beneath it.
+pub(crate) fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Table(base), Value::Table(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(slot) => merge(slot, value),
+                    None => { base.insert(key, value); }
+                }
+            }
+        }
+        (slot, value) => *slot = value,
+    }
+}
@@ -0,0 +1,39 @@
+use std::fmt::{Display, Formatter, self};
+
+
+macro_rules! name_type {
+    ($($(#[$meta:meta])* $name:ident;)*) => {$(
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+        pub struct $name<'a>(pub &'a str);
+
+        impl<'a> $name<'a> {
+            /// Return the name as a string slice.
+            pub fn as_str(&self) -> &'a str { self.0 }
+        }
+
+        impl<'a> From<&'a str> for $name<'a> {
+            fn from(name: &'a str) -> Self { Self(name) }
+        }
+
+        impl Display for $name<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+    )*};
+}
+
+
+name_type! {
+    /// A reverse domain qualifier, such as `"com"` or `"org"`. Only used on
+    ///     macOS, and may be empty.
+    Qualifier;
+    /// The name of the organization responsible for an application. May be
+    ///     empty.
+    Organization;
+    /// The name of an application.
+    Application;
+    /// The filename of a configuration file, such as `"config.toml"`.
+    FileName;
+}
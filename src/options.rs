@@ -0,0 +1,452 @@
+use std::{path::PathBuf, time::Duration};
+#[cfg(feature = "fs")]
+use std::{io::ErrorKind, thread::sleep};
+
+
+/// The sequence of characters used to end each line of a written file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineEnding {
+    /// A single line feed, as used on most platforms.
+    Lf,
+    /// A carriage return followed by a line feed, as used on Windows.
+    Crlf,
+    /// Whichever of the above is conventional on the current platform.
+    #[default]
+    Native,
+}
+
+impl LineEnding {
+    /// Return the characters which end each line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+            Self::Native if cfg!(windows) => "\r\n",
+            Self::Native => "\n",
+        }
+    }
+}
+
+
+/// What to do when saving a backup, if a backup from an earlier save already
+///     exists at the same path.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BackupPolicy {
+    /// Replace the earlier backup with the new one.
+    #[default]
+    Overwrite,
+    /// Keep the earlier backup, and do not save a new one.
+    Skip,
+    /// Keep the earlier backup, and save the new one with the first numeric
+    ///     suffix which is not taken, such as `.bkp.config.toml.1`.
+    Suffix,
+}
+
+
+/// A policy for retrying file operations which fail with transient errors, as
+///     may happen on network filesystems.
+///
+/// Only errors of the kinds [`Interrupted`], [`WouldBlock`] and [`TimedOut`]
+///     are retried. Any other error, such as a denial of permission, is
+///     returned immediately.
+///
+/// [`Interrupted`]: std::io::ErrorKind::Interrupted
+/// [`TimedOut`]: std::io::ErrorKind::TimedOut
+/// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The greatest number of times an operation is attempted, including the
+    ///     first attempt. A value of `0` is treated as `1`.
+    pub max_attempts: u32,
+    /// The time to wait before the first retry. The wait is doubled before
+    ///     each subsequent retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy which never retries an operation.
+    pub const NONE: Self = Self { max_attempts: 1, backoff: Duration::ZERO };
+
+    /// Create a policy with the given number of attempts and initial backoff.
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self { max_attempts, backoff }
+    }
+
+    /// Run an operation, retrying it according to this policy.
+    #[cfg(feature = "fs")]
+    pub(crate) fn run<T>(
+        &self,
+        mut op: impl FnMut() -> Result<T, std::io::Error>,
+    ) -> Result<T, std::io::Error> {
+        let mut wait = self.backoff;
+
+        for _ in 1..self.max_attempts {
+            match op() {
+                Err(e) if is_transient(e.kind()) => {
+                    sleep(wait);
+                    wait = wait.saturating_mul(2);
+                }
+                result => { return result; }
+            }
+        }
+
+        op()
+    }
+}
+
+
+impl Default for RetryPolicy {
+    fn default() -> Self { Self::NONE }
+}
+
+
+/// Return whether an error of the given kind may succeed if retried.
+#[cfg(feature = "fs")]
+fn is_transient(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut,
+    )
+}
+
+
+/// Options controlling the layout of text produced by the [`Toml`] format.
+///
+/// The defaults match the compact output of [`toml::to_string`]. The other
+///     options only take effect when [`pretty`] is enabled. Other formats
+///     ignore these options.
+///
+/// [`pretty`]: Self::pretty
+/// [`Toml`]: crate::Toml
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TomlStyle {
+    /// Whether to write arrays across several lines, with one element on each
+    ///     line. Defaults to `false`.
+    pub pretty: bool,
+    /// The number of spaces by which each element of an array is indented.
+    ///     Defaults to `4`.
+    pub array_indent: usize,
+    /// Whether to write a comma after the last element of an array. Defaults
+    ///     to `true`.
+    pub array_trailing_comma: bool,
+    /// Whether to write strings which contain newlines as multi-line strings.
+    ///     Defaults to `true`.
+    pub multiline_strings: bool,
+}
+
+impl Default for TomlStyle {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            array_indent: 4,
+            array_trailing_comma: true,
+            multiline_strings: true,
+        }
+    }
+}
+
+
+/// Options controlling how a configuration file is written.
+///
+/// Options are set by chaining the builder methods which share their names,
+///     starting from [`SaveOptions::default`] or [`SaveOptions::new`], or by
+///     assigning to the fields of an existing value. More options may be added
+///     in future versions, so the struct cannot be built from its fields
+///     directly:
+///
+/// ```
+/// use tomlconf::{LineEnding, SaveOptions};
+///
+/// let opts = SaveOptions::default()
+///     .with_backup(false)
+///     .with_atomic(true)
+///     .with_line_ending(LineEnding::Lf);
+///
+/// assert!(!opts.create_backup);
+/// assert!(opts.create_parent);
+/// assert!(opts.atomic);
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SaveOptions {
+    /// Whether to try to save a backup of the current file, if it already
+    ///     exists. Defaults to `true`.
+    pub create_backup: bool,
+    /// Whether to try to create the parent directory for the file, if it does
+    ///     not exist. Defaults to `true`.
+    pub create_parent: bool,
+    /// A directory in which to place backups, instead of placing them next to
+    ///     the file itself. A relative path is taken to be relative to the
+    ///     directory containing the file. The directory will be created if it
+    ///     does not exist. Defaults to [`None`].
+    pub backup_dir: Option<PathBuf>,
+    /// What to do if a backup already exists where a new one would be saved.
+    ///     Defaults to [`BackupPolicy::Overwrite`].
+    pub backup_policy: BackupPolicy,
+    /// Whether to write a checksum file alongside the file, which may later be
+    ///     verified by `ConfigData::open_verified`. This is ignored unless the
+    ///     `checksum` feature is enabled. Defaults to `false`.
+    pub write_checksum: bool,
+    /// Whether to ensure that the written text ends with exactly one newline.
+    ///     Disable this to write the output of the format exactly as it was
+    ///     produced. Defaults to `true`.
+    pub trailing_newline: bool,
+    /// The line ending to which every line of the written text is converted.
+    ///     Defaults to [`LineEnding::Native`].
+    pub line_ending: LineEnding,
+    /// The policy for retrying file operations which fail with transient
+    ///     errors. Defaults to [`RetryPolicy::NONE`].
+    pub retry: RetryPolicy,
+    /// Whether to make the file read-only after it is written, in order to
+    ///     discourage accidental edits. On Unix, this removes all write
+    ///     permissions. Defaults to `false`.
+    ///
+    /// See [`ConfigFile::set_read_only`] to make the file writable again.
+    ///
+    /// [`ConfigFile::set_read_only`]: crate::ConfigFile::set_read_only
+    pub read_only: bool,
+    /// The permissions given to any directory created to hold the file or its
+    ///     backups, such as `0o700` to keep it private to its owner. Existing
+    ///     directories are left unchanged, and the mode is still limited by
+    ///     the umask of the process. This is ignored on platforms other than
+    ///     Unix. Defaults to [`None`], using the usual permissions.
+    pub dir_mode: Option<u32>,
+    /// The permissions given to the file when it is written, such as `0o600`
+    ///     to keep it private to its owner. These are applied to the file as
+    ///     soon as it is opened, before any text is written into it. Defaults
+    ///     to [`None`], keeping the permissions of an existing file, or using
+    ///     the usual permissions for a new one. This is ignored on platforms
+    ///     other than Unix.
+    pub file_mode: Option<u32>,
+    /// Whether to write the text into a temporary file in the same directory,
+    ///     and then move it into place, so that the file is never left partly
+    ///     written. If this fails, the temporary file is removed, and the
+    ///     existing file is left as it was. Any backup is made by linking or
+    ///     copying the existing file, rather than moving it, so that the file
+    ///     is never missing. Defaults to `false`.
+    pub atomic: bool,
+    /// The suffix given to the temporary file used by [`atomic`] saves. The
+    ///     temporary file is named after the file, followed by the ID of the
+    ///     process and then this suffix, such as `config.toml.1234.tmp`. It
+    ///     must not contain a path separator, so that the temporary file stays
+    ///     in the same directory; Otherwise, the save fails. Defaults to
+    ///     `".tmp"`.
+    ///
+    /// [`atomic`]: Self::atomic
+    pub temp_suffix: String,
+    /// Whether to prefix the name of the temporary file used by [`atomic`]
+    ///     saves with a dot, hiding it from most directory listings, such as
+    ///     `.config.toml.1234.tmp`. Defaults to `true`.
+    ///
+    /// [`atomic`]: Self::atomic
+    pub temp_hidden: bool,
+    /// Whether to write through a symbolic link at the path of the file, into
+    ///     the file to which it points, such as a file kept in a repository of
+    ///     dotfiles. Any backup is then placed next to that file. Otherwise,
+    ///     the link itself is replaced by a regular file. Defaults to `true`.
+    pub follow_symlinks: bool,
+    /// Whether to wait until the file, and the directory entry naming it, have
+    ///     reached the storage device before returning, so that the save will
+    ///     survive a crash or a loss of power. This may take a long time on a
+    ///     slow device, so it is best avoided when saving often. Defaults to
+    ///     `false`.
+    pub sync: bool,
+    /// Options controlling the layout of text produced by the [`Toml`] format.
+    ///
+    /// [`Toml`]: crate::Toml
+    pub toml: TomlStyle,
+}
+
+impl SaveOptions {
+    /// Create options with the given backup and parent directory behavior, and
+    ///     all other options set to their defaults.
+    pub fn new(create_backup: bool, create_parent: bool) -> Self {
+        Self { create_backup, create_parent, ..Self::default() }
+    }
+
+    /// Set whether to save a backup of the current file. See [`create_backup`].
+    ///
+    /// [`create_backup`]: Self::create_backup
+    pub fn with_backup(self, create_backup: bool) -> Self {
+        Self { create_backup, ..self }
+    }
+
+    /// Set whether to create the parent directory of the file. See
+    ///     [`create_parent`].
+    ///
+    /// [`create_parent`]: Self::create_parent
+    pub fn with_parent(self, create_parent: bool) -> Self {
+        Self { create_parent, ..self }
+    }
+
+    /// Set the directory in which to place backups. See [`backup_dir`].
+    ///
+    /// [`backup_dir`]: Self::backup_dir
+    pub fn with_backup_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self { backup_dir: Some(dir.into()), ..self }
+    }
+
+    /// Set what to do if a backup already exists. See [`backup_policy`].
+    ///
+    /// [`backup_policy`]: Self::backup_policy
+    pub fn with_backup_policy(self, backup_policy: BackupPolicy) -> Self {
+        Self { backup_policy, ..self }
+    }
+
+    /// Set whether to write a checksum file alongside the file. See
+    ///     [`write_checksum`].
+    ///
+    /// [`write_checksum`]: Self::write_checksum
+    pub fn with_checksum(self, write_checksum: bool) -> Self {
+        Self { write_checksum, ..self }
+    }
+
+    /// Set whether to end the text with exactly one newline. See
+    ///     [`trailing_newline`].
+    ///
+    /// [`trailing_newline`]: Self::trailing_newline
+    pub fn with_trailing_newline(self, trailing_newline: bool) -> Self {
+        Self { trailing_newline, ..self }
+    }
+
+    /// Set the line ending of the written text. See [`line_ending`].
+    ///
+    /// [`line_ending`]: Self::line_ending
+    pub fn with_line_ending(self, line_ending: LineEnding) -> Self {
+        Self { line_ending, ..self }
+    }
+
+    /// Set the policy for retrying transient failures. See [`retry`].
+    ///
+    /// [`retry`]: Self::retry
+    pub fn with_retry(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// Set whether to make the file read-only. See [`read_only`].
+    ///
+    /// [`read_only`]: Self::read_only
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set the permissions of created directories. See [`dir_mode`].
+    ///
+    /// [`dir_mode`]: Self::dir_mode
+    pub fn with_dir_mode(self, mode: u32) -> Self {
+        Self { dir_mode: Some(mode), ..self }
+    }
+
+    /// Set the permissions of the file. See [`file_mode`].
+    ///
+    /// [`file_mode`]: Self::file_mode
+    pub fn with_file_mode(self, mode: u32) -> Self {
+        Self { file_mode: Some(mode), ..self }
+    }
+
+    /// Keep the file, and any directories created to hold it, private to their
+    ///     owner, by setting [`dir_mode`] to `0o700` and [`file_mode`] to
+    ///     `0o600`. This is suitable for files which hold credentials. It has
+    ///     no effect on platforms other than Unix.
+    ///
+    /// [`dir_mode`]: Self::dir_mode
+    /// [`file_mode`]: Self::file_mode
+    pub fn private(self) -> Self {
+        self.with_dir_mode(0o700).with_file_mode(0o600)
+    }
+
+    /// Set whether to write the file atomically. See [`atomic`].
+    ///
+    /// [`atomic`]: Self::atomic
+    pub fn with_atomic(self, atomic: bool) -> Self {
+        Self { atomic, ..self }
+    }
+
+    /// Set the suffix of the temporary file used by atomic saves. See
+    ///     [`temp_suffix`].
+    ///
+    /// [`temp_suffix`]: Self::temp_suffix
+    pub fn with_temp_suffix(self, temp_suffix: impl Into<String>) -> Self {
+        Self { temp_suffix: temp_suffix.into(), ..self }
+    }
+
+    /// Set whether to hide the temporary file used by atomic saves. See
+    ///     [`temp_hidden`].
+    ///
+    /// [`temp_hidden`]: Self::temp_hidden
+    pub fn with_temp_hidden(self, temp_hidden: bool) -> Self {
+        Self { temp_hidden, ..self }
+    }
+
+    /// Set whether to write through a symbolic link. See [`follow_symlinks`].
+    ///
+    /// [`follow_symlinks`]: Self::follow_symlinks
+    pub fn with_follow_symlinks(self, follow_symlinks: bool) -> Self {
+        Self { follow_symlinks, ..self }
+    }
+
+    /// Set whether to wait for the file to reach the storage device. See
+    ///     [`sync`].
+    ///
+    /// [`sync`]: Self::sync
+    pub fn with_sync(self, sync: bool) -> Self {
+        Self { sync, ..self }
+    }
+
+    /// Set the layout of text produced by the [`Toml`] format. See [`toml`].
+    ///
+    /// [`Toml`]: crate::Toml
+    /// [`toml`]: Self::toml
+    pub fn with_toml(self, toml: TomlStyle) -> Self {
+        Self { toml, ..self }
+    }
+
+    /// Apply any options which affect the text itself, before it is written.
+    ///     Line endings are converted after the trailing newline is added.
+    pub(crate) fn normalize(&self, mut text: String) -> String {
+        if self.trailing_newline && !text.is_empty() {
+            text.truncate(text.trim_end_matches(['\r', '\n']).len());
+            text.push('\n');
+        }
+
+        let eol = self.line_ending.as_str();
+        let mut out = String::with_capacity(text.len());
+
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(line) => {
+                    out.push_str(line.strip_suffix('\r').unwrap_or(line));
+                    out.push_str(eol);
+                }
+                None => out.push_str(line),
+            }
+        }
+
+        out
+    }
+}
+
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            create_backup: true,
+            create_parent: true,
+            backup_dir: None,
+            backup_policy: BackupPolicy::Overwrite,
+            write_checksum: false,
+            trailing_newline: true,
+            line_ending: LineEnding::Native,
+            retry: RetryPolicy::NONE,
+            read_only: false,
+            dir_mode: None,
+            file_mode: None,
+            atomic: false,
+            temp_suffix: String::from(".tmp"),
+            temp_hidden: true,
+            follow_symlinks: true,
+            sync: false,
+            toml: TomlStyle::default(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::io::Error;
+use tokio::{signal::unix::{signal, SignalKind}, sync::watch};
+use crate::{
+    config::{ConfigData, ConfigFile, ConfigOpen},
+    format::ConfigFormat,
+};
+
+
+impl<Cfg> ConfigFile<Cfg> {
+    /// Reread the file whenever the process receives `SIGHUP`, as is the usual
+    ///     way of asking a Unix daemon to reload its configuration, and publish
+    ///     each new configuration through a [`watch`] channel.
+    ///
+    /// The channel initially holds the current configuration. If the file can
+    ///     no longer be read, the last good configuration is kept, and the
+    ///     failure is passed to `on_error`, which may report it to the user.
+    ///     The task which waits for the signal ends when it next receives one
+    ///     after every receiver has been dropped.
+    ///
+    /// This must be called from within a Tokio runtime, and returns an error if
+    ///     the signal handler cannot be installed. It is only available on Unix,
+    ///     with the `tokio` feature.
+    pub fn reload_on_sighup<F>(
+        self,
+        mut on_error: impl FnMut(ConfigOpen<Cfg>) + Send + 'static,
+    ) -> Result<watch::Receiver<Cfg>, Error>
+        where
+            Cfg: ConfigData<F> + Send + Sync + 'static,
+            F: ConfigFormat + 'static,
+    {
+        let mut hangups = signal(SignalKind::hangup())?;
+        let (sender, receiver) = watch::channel(self.data);
+        let path = self.path;
+
+        tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                match Cfg::open(&path) {
+                    ConfigOpen::FileValid(cfg) => {
+                        if sender.send(cfg).is_err() {
+                            break;
+                        }
+                    }
+                    err => on_error(err),
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
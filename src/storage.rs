@@ -0,0 +1,522 @@
+use std::{path::Path, sync::{Mutex, PoisonError}};
+#[cfg(feature = "fs")]
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs::{DirBuilder, File, OpenOptions, remove_file, rename},
+    io::{Read, Write},
+    path::PathBuf,
+};
+use crate::options::SaveOptions;
+#[cfg(feature = "fs")]
+use crate::config::{link_backup, prepare_write};
+
+
+/// A place where the text of a configuration may be stored.
+///
+/// This is implemented for filesystem paths, which is how a [`ConfigFile`] is
+///     normally stored, as well as for [`InMemory`] buffers, which never touch
+///     the disk.
+///
+/// With the `gzip` feature, a path whose name ends in `.gz` is transparently
+///     decompressed when read, and compressed when written.
+///
+/// [`ConfigFile`]: crate::ConfigFile
+pub trait ConfigStorage {
+    /// Read the full text currently held in this storage.
+    fn read(&self) -> Result<String, std::io::Error>;
+
+    /// Replace the text held in this storage. Options which do not apply to a
+    ///     particular kind of storage are ignored.
+    fn write(&self, text: &str, opts: &SaveOptions) -> Result<(), std::io::Error>;
+
+    /// Return the path of the file in which the text is stored, if this is a
+    ///     file. Returns [`None`] by default.
+    fn path(&self) -> Option<&Path> { None }
+}
+
+
+/// Return the bytes to be written into the file at a path.
+#[cfg(feature = "fs")]
+pub(crate) fn encode<'t>(
+    path: &Path,
+    text: &'t str,
+) -> Result<Cow<'t, [u8]>, std::io::Error> {
+    #[cfg(feature = "gzip")]
+    if crate::gzip::is_compressed(path) {
+        return crate::gzip::compress(text).map(Cow::Owned);
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    let _ = path;
+
+    Ok(Cow::Borrowed(text.as_bytes()))
+}
+
+
+/// Return the text held in the bytes read from the file at a path.
+#[cfg(feature = "checksum")]
+pub(crate) fn decode(
+    path: &Path,
+    bytes: Vec<u8>,
+) -> Result<String, std::io::Error> {
+    #[cfg(feature = "gzip")]
+    if crate::gzip::is_compressed(path) {
+        return crate::gzip::decompress(&bytes);
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    let _ = path;
+
+    String::from_utf8(bytes).map_err(|e| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    })
+}
+
+
+/// Remove the write permissions of a file, or restore write permission for
+///     its owner.
+#[cfg(feature = "fs")]
+pub(crate) fn set_read_only(
+    path: &Path,
+    read_only: bool,
+) -> Result<(), std::io::Error> {
+    let mut perms = path.metadata()?.permissions();
+
+    #[cfg(unix)] {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = perms.mode();
+        perms.set_mode(if read_only { mode & !0o222 } else { mode | 0o200 });
+    }
+
+    #[cfg(not(unix))]
+    perms.set_readonly(read_only);
+
+    std::fs::set_permissions(path, perms)
+}
+
+
+/// Create a directory and all of its missing parents, giving each the mode
+///     requested by the options.
+#[cfg(feature = "fs")]
+pub(crate) fn create_dir(
+    path: &Path,
+    opts: &SaveOptions,
+) -> Result<(), std::io::Error> {
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    if let Some(mode) = opts.dir_mode {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(mode);
+    }
+
+    #[cfg(not(unix))]
+    let _ = opts;
+
+    builder.create(path)
+}
+
+
+/// Open a file for writing, replacing its contents, and give it the mode
+///     requested by the options, before anything is written into it.
+#[cfg(feature = "fs")]
+fn create_file(
+    path: &Path,
+    opts: &SaveOptions,
+) -> Result<File, std::io::Error> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    if let Some(mode) = opts.file_mode {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(mode);
+        let file = options.open(path)?;
+        file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+        return Ok(file);
+    }
+
+    #[cfg(not(unix))]
+    let _ = opts;
+
+    options.open(path)
+}
+
+
+/// Give a temporary file the permissions of the existing file which it will
+///     replace, so that an atomic write does not change them, unless the
+///     options request permissions of their own.
+#[cfg(feature = "fs")]
+fn keep_permissions(
+    file: &File,
+    path: &Path,
+    opts: &SaveOptions,
+) -> Result<(), std::io::Error> {
+    #[cfg(unix)]
+    if opts.file_mode.is_some() {
+        return Ok(());
+    }
+
+    #[cfg(not(unix))]
+    let _ = opts;
+
+    match path.metadata() {
+        Ok(meta) => file.set_permissions(meta.permissions()),
+        Err(..) => Ok(()),
+    }
+}
+
+
+/// Return the path of the temporary file into which a file at the given path
+///     is written before being moved into place. The name is given by the
+///     options, but may not contain a path separator, so that the file stays
+///     in the same directory.
+#[cfg(feature = "fs")]
+fn temp_path(
+    path: &Path,
+    opts: &SaveOptions,
+) -> Result<PathBuf, std::io::Error> {
+    if opts.temp_suffix.chars().any(std::path::is_separator) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Temporary suffix {:?} must not contain a path separator",
+                opts.temp_suffix,
+            ),
+        ));
+    }
+
+    let mut name = OsString::new();
+
+    if opts.temp_hidden {
+        name.push(".");
+    }
+
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{}{}", std::process::id(), opts.temp_suffix));
+    Ok(path.with_file_name(name))
+}
+
+
+/// Write bytes into a temporary file, and then move it into place at the given
+///     path, taking a backup of the existing file in between. If any step
+///     fails, the temporary file is removed, and the error from the failed
+///     step is returned.
+#[cfg(feature = "fs")]
+fn write_atomic(
+    path: &Path,
+    bytes: &[u8],
+    opts: &SaveOptions,
+) -> Result<(), std::io::Error> {
+    let temp = temp_path(path, opts)?;
+    let result = create_file(&temp, opts)
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            keep_permissions(&file, path, opts)?;
+            file.sync_all()
+        })
+        .and_then(|()| {
+            link_backup(path, opts);
+            rename(&temp, path)
+        });
+
+    if result.is_err() {
+        remove_file(&temp).ok();
+    }
+
+    result
+}
+
+
+/// Write bytes into the file at the given path, replacing its contents, and
+///     wait for them to reach the storage device if requested.
+#[cfg(feature = "fs")]
+fn write_direct(
+    path: &Path,
+    bytes: &[u8],
+    opts: &SaveOptions,
+) -> Result<(), std::io::Error> {
+    let mut file = create_file(path, opts)?;
+
+    file.write_all(bytes)?;
+
+    match opts.sync {
+        true => file.sync_all(),
+        false => Ok(()),
+    }
+}
+
+
+/// Wait for the entries of the directory containing a file to reach the
+///     storage device, so that a newly created or renamed file is not lost.
+///     This is only possible on Unix, and does nothing elsewhere.
+#[cfg(feature = "fs")]
+fn sync_parent(path: &Path) -> Result<(), std::io::Error> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = match parent.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => parent,
+        };
+
+        File::open(parent)?.sync_all()?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+
+/// Return the file to which a symbolic link at the given path ultimately
+///     points, or the path itself if it is not a link. A link whose target
+///     cannot be resolved, such as one whose target does not yet exist, is
+///     also returned as it is.
+#[cfg(feature = "fs")]
+pub(crate) fn resolve_link(path: &Path) -> Cow<'_, Path> {
+    let is_link = path.symlink_metadata()
+        .is_ok_and(|meta| meta.file_type().is_symlink());
+
+    match is_link {
+        true => path.canonicalize().map_or(Cow::Borrowed(path), Cow::Owned),
+        false => Cow::Borrowed(path),
+    }
+}
+
+
+/// Remove a symbolic link at the given path, so that a file may be created in
+///     its place, rather than being written through it. Anything other than a
+///     link is left alone.
+#[cfg(feature = "fs")]
+fn remove_link(path: &Path) -> Result<(), std::io::Error> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.file_type().is_symlink() => remove_file(path),
+        _ => Ok(()),
+    }
+}
+
+
+/// The largest buffer which will be allocated up front when reading a file.
+///     Larger files are still read in full, growing the buffer as needed.
+#[cfg(feature = "fs")]
+const PREALLOC_LIMIT: usize = 1 << 20;
+
+
+/// Return the capacity of a buffer into which to read a file of the given
+///     length. The length is only a hint, since the file may change while it
+///     is being read, and may not fit into a `usize` on 32-bit targets.
+#[cfg(feature = "fs")]
+fn capacity_for(len: u64) -> usize {
+    usize::try_from(len)
+        .unwrap_or(usize::MAX)
+        .min(PREALLOC_LIMIT)
+        .saturating_add(1)
+}
+
+
+#[cfg(feature = "fs")]
+impl ConfigStorage for Path {
+    fn read(&self) -> Result<String, std::io::Error> {
+        #[cfg(feature = "gzip")]
+        if crate::gzip::is_compressed(self) {
+            return crate::gzip::read(self);
+        }
+
+        let mut file = File::open(self)?;
+        let len = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+
+        let mut buf = String::with_capacity(capacity_for(len));
+        file.read_to_string(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    fn write(&self, text: &str, opts: &SaveOptions) -> Result<(), std::io::Error> {
+        let bytes = encode(self, text)?;
+        let target = match opts.follow_symlinks {
+            true => resolve_link(self),
+            false => Cow::Borrowed(self),
+        };
+
+        opts.retry.run(|| prepare_write(&target, opts))?;
+        opts.retry.run(|| match opts.atomic {
+            true => write_atomic(&target, &bytes, opts),
+            false if opts.follow_symlinks => {
+                write_direct(&target, &bytes, opts)
+            }
+            false => {
+                remove_link(&target)?;
+                write_direct(&target, &bytes, opts)
+            }
+        })?;
+
+        if opts.sync {
+            sync_parent(&target)?;
+        }
+
+        #[cfg(feature = "checksum")]
+        if opts.write_checksum {
+            crate::checksum::write(self, &bytes)?;
+        }
+
+        if opts.read_only {
+            set_read_only(&target, true)?;
+        }
+
+        Ok(())
+    }
+
+    fn path(&self) -> Option<&Path> { Some(self) }
+}
+
+
+#[cfg(feature = "fs")]
+impl ConfigStorage for PathBuf {
+    fn read(&self) -> Result<String, std::io::Error> {
+        self.as_path().read()
+    }
+
+    fn write(&self, text: &str, opts: &SaveOptions) -> Result<(), std::io::Error> {
+        self.as_path().write(text, opts)
+    }
+
+    fn path(&self) -> Option<&Path> { Some(self) }
+}
+
+
+/// A text buffer held in memory, which may be used in place of a file path in
+///     order to create a [`ConfigFile`] that never touches the disk.
+///
+/// Saving a configuration into this storage replaces the text of the buffer,
+///     and reloading a configuration parses it again. This is mostly useful in
+///     tests.
+///
+/// [`ConfigFile`]: crate::ConfigFile
+#[derive(Debug, Default)]
+pub struct InMemory {
+    text: Mutex<String>,
+}
+
+impl InMemory {
+    /// Create a new buffer holding the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: Mutex::new(text.into()) }
+    }
+
+    /// Return a copy of the text currently held in the buffer.
+    pub fn text(&self) -> String {
+        self.text.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+
+impl Clone for InMemory {
+    fn clone(&self) -> Self {
+        Self::new(self.text())
+    }
+}
+
+
+impl ConfigStorage for InMemory {
+    fn read(&self) -> Result<String, std::io::Error> {
+        Ok(self.text())
+    }
+
+    fn write(&self, text: &str, _opts: &SaveOptions) -> Result<(), std::io::Error> {
+        let mut buf = self.text.lock().unwrap_or_else(PoisonError::into_inner);
+
+        buf.clear();
+        buf.push_str(text);
+        Ok(())
+    }
+}
+
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use super::*;
+    use crate::testing::scratch;
+
+    #[test]
+    #[cfg(unix)]
+    fn atomic_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = scratch("atomic-perms").join("config.toml");
+        let mode = |path: &Path| {
+            path.metadata().unwrap().permissions().mode() & 0o777
+        };
+
+        std::fs::write(&path, "a = 1").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .unwrap();
+
+        let opts = SaveOptions::default().with_atomic(true);
+        path.as_path().write("a = 2", &opts).unwrap();
+        assert_eq!(mode(&path), 0o600);
+
+        path.as_path().write("a = 3", &opts.with_file_mode(0o640)).unwrap();
+        assert_eq!(mode(&path), 0o640);
+    }
+
+    #[test]
+    fn failed_atomic_write_removes_temp() {
+        let dir = scratch("atomic-fail");
+        let path = dir.join("config.toml");
+
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("inner"), "").unwrap();
+
+        let opts = SaveOptions::new(false, false).with_atomic(true);
+        assert!(write_atomic(&path, b"a = 1", &opts).is_err());
+
+        let names: Vec<_> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["config.toml"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn write_follows_symlink() {
+        let dir = scratch("follow");
+        let (link, target) = (dir.join("config.toml"), dir.join("real.toml"));
+
+        std::fs::write(&target, "a = 1").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let opts = SaveOptions::new(false, true);
+        link.as_path().write("a = 2", &opts).unwrap();
+
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "a = 2");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn write_replaces_symlink() {
+        let dir = scratch("no-follow");
+        let (link, target) = (dir.join("config.toml"), dir.join("real.toml"));
+
+        std::fs::write(&target, "a = 1").unwrap();
+
+        for atomic in [false, true] {
+            std::fs::remove_file(&link).ok();
+            std::os::unix::fs::symlink(&target, &link).unwrap();
+
+            let opts = SaveOptions::new(false, true)
+                .with_follow_symlinks(false)
+                .with_atomic(atomic);
+            link.as_path().write("a = 2", &opts).unwrap();
+
+            assert!(link.symlink_metadata().unwrap().is_file());
+            assert_eq!(std::fs::read_to_string(&link).unwrap(), "a = 2");
+            assert_eq!(std::fs::read_to_string(&target).unwrap(), "a = 1");
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+#[cfg(feature = "directories")]
+use crate::config::{DirKind, find_path};
+
+
+/// A way of deciding where a configuration file should be, separate from how
+///     it is read.
+///
+/// This is implemented for explicit paths, for [`InCurrentDir`], and, with the
+///     `directories` feature, for [`InProjectDirs`]. Other implementations may
+///     resolve the path in any way suited to a particular deployment, such as
+///     from a command line argument or a fixed system location. Any of them
+///     may be passed to [`ConfigData::find_by`] and [`ConfigData::setup_by`].
+///
+/// [`ConfigData::find_by`]: crate::ConfigData::find_by
+/// [`ConfigData::setup_by`]: crate::ConfigData::setup_by
+pub trait PathStrategy {
+    /// Return the path at which the configuration file should be, or [`None`]
+    ///     if no path can be determined.
+    fn config_path(&self) -> Option<PathBuf>;
+}
+
+
+impl PathStrategy for Path {
+    fn config_path(&self) -> Option<PathBuf> { Some(self.to_owned()) }
+}
+
+
+impl PathStrategy for PathBuf {
+    fn config_path(&self) -> Option<PathBuf> { Some(self.clone()) }
+}
+
+
+impl<T: PathStrategy + ?Sized> PathStrategy for &T {
+    fn config_path(&self) -> Option<PathBuf> { (**self).config_path() }
+}
+
+
+/// A file with the given name in the current working directory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InCurrentDir<'a>(pub &'a str);
+
+impl PathStrategy for InCurrentDir<'_> {
+    fn config_path(&self) -> Option<PathBuf> {
+        std::env::current_dir().ok().map(|dir| dir.join(self.0))
+    }
+}
+
+
+/// A file in a directory of an application defined by [`ProjectDirs`], as used
+///     by [`ConfigData::find_with_kind`].
+///
+/// [`ConfigData::find_with_kind`]: crate::ConfigData::find_with_kind
+/// [`ProjectDirs`]: directories::ProjectDirs
+#[cfg(feature = "directories")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InProjectDirs<'a> {
+    /// The kind of directory in which to place the file.
+    pub kind: DirKind,
+    /// Reverse domain qualifier. May be empty.
+    pub qualifier: &'a str,
+    /// The organization responsible for the application. May be empty.
+    pub organization: &'a str,
+    /// The name of the application itself.
+    pub application: &'a str,
+    /// The filename to be used for the configuration file.
+    pub file: &'a str,
+}
+
+#[cfg(feature = "directories")]
+impl PathStrategy for InProjectDirs<'_> {
+    fn config_path(&self) -> Option<PathBuf> {
+        find_path(
+            self.kind,
+            self.qualifier, self.organization, self.application, self.file,
+        )
+    }
+}
@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+
+/// Return an empty directory, unique to this process and the given name, in
+///     which a test may freely create files.
+pub(crate) fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("tomlconf-{}-{}", std::process::id(), name));
+
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,60 @@
+/// Check every element of a list with the same function, and describe the
+///     first failure along with the index of the element which caused it, such
+///     as `server[2]: port out of range`.
+///
+/// This is intended to be used in [`ConfigData::validate`], for fields holding
+///     arrays of tables, where an error would otherwise not say which element
+///     is at fault. The name should be the key of the field in the file.
+///
+/// [`ConfigData::validate`]: crate::ConfigData::validate
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+/// use tomlconf::{ConfigData, validate_each};
+///
+/// #[derive(Deserialize)]
+/// struct Server { host: String, port: u16 }
+///
+/// #[derive(Deserialize)]
+/// struct Config { server: Vec<Server> }
+///
+/// impl ConfigData for Config {
+///     const DEFAULT: &'static str = "server = []";
+///
+///     fn validate(&self) -> Result<(), String> {
+///         validate_each("server", &self.server, |server| {
+///             match server.port {
+///                 0 => Err(String::from("port out of range")),
+///                 _ => Ok(()),
+///             }
+///         })
+///     }
+/// }
+///
+/// let config = Config::from_text(String::from(r#"
+///     [[server]]
+///     host = "a"
+///     port = 80
+///
+///     [[server]]
+///     host = "b"
+///     port = 0
+/// "#)).unwrap();
+///
+/// assert_eq!(config.validate().unwrap_err(), "server[1]: port out of range");
+/// ```
+pub fn validate_each<T>(
+    name: &str,
+    items: &[T],
+    check: impl Fn(&T) -> Result<(), String>,
+) -> Result<(), String> {
+    for (index, item) in items.iter().enumerate() {
+        if let Err(msg) = check(item) {
+            return Err(format!("{}[{}]: {}", name, index, msg));
+        }
+    }
+
+    Ok(())
+}
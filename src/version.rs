@@ -0,0 +1,70 @@
+//! Version markers, recording which version of an application last wrote the
+//!     default configuration file.
+//!
+//! The marker is a plain text file beside the configuration file, named after
+//!     it with `.version` appended, so that `config.toml` is described by
+//!     `config.toml.version`. It holds a single line with the version string,
+//!     and any surrounding whitespace is ignored when it is read.
+
+use std::{
+    ffi::OsString,
+    fs::write,
+    io,
+    path::{Path, PathBuf},
+};
+#[cfg(feature = "directories")]
+use std::{cmp::Ordering, fs::read_to_string, io::ErrorKind};
+
+
+/// Return the path of the version marker describing a configuration file.
+pub(crate) fn marker_path(path: &Path) -> PathBuf {
+    let mut name: OsString = path.file_name().unwrap_or_default().to_owned();
+
+    name.push(".version");
+    path.with_file_name(name)
+}
+
+
+/// Read the version recorded beside a configuration file. Return [`None`] if
+///     there is no marker.
+#[cfg(feature = "directories")]
+pub(crate) fn read(path: &Path) -> io::Result<Option<String>> {
+    match read_to_string(marker_path(path)) {
+        Ok(text) => Ok(Some(text.trim().to_owned())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+
+/// Record a version beside a configuration file.
+pub(crate) fn write_marker(path: &Path, version: &str) -> io::Result<()> {
+    write(marker_path(path), format!("{}\n", version.trim()))
+}
+
+
+/// Determine whether a recorded version is older than the current one.
+///
+/// Versions made only of numbers separated by dots, such as `1.10.2`, are
+///     compared numerically, component by component, with missing components
+///     counting as zero. Any other version is considered older whenever it
+///     differs at all, since no order can be assumed.
+#[cfg(feature = "directories")]
+pub(crate) fn is_older(recorded: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        version.trim().split('.').map(|n| n.parse().ok()).collect()
+    }
+
+    match (parse(recorded), parse(current)) {
+        (Some(old), Some(new)) => {
+            let len = old.len().max(new.len());
+            let get = |v: &[u64], i| v.get(i).copied().unwrap_or(0);
+
+            (0..len)
+                .map(|i| get(&old, i).cmp(&get(&new, i)))
+                .find(|ord| ord.is_ne())
+                == Some(Ordering::Less)
+        }
+        _ => recorded.trim() != current.trim(),
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter, self};
+
+
+/// A problem with a configuration which does not prevent it from being used,
+///     but which the user should be told about, returned by
+///     [`ConfigData::open_with_warnings`].
+///
+/// Warnings of every kind may be produced by an implementation of
+///     [`ConfigData::prepare_with_warnings`]; Unknown keys are also found
+///     automatically.
+///
+/// More kinds of warning may be added in future versions, so a `match` on this
+///     type must include a wildcard arm.
+///
+/// [`ConfigData::open_with_warnings`]: crate::ConfigData::open_with_warnings
+/// [`ConfigData::prepare_with_warnings`]: crate::ConfigData::prepare_with_warnings
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// The file contains a key which the configuration does not declare, such
+    ///     as a misspelled field name. The key was ignored.
+    UnknownKey(String),
+    /// The file sets a key which is still accepted, but should no longer be
+    ///     used.
+    Deprecated(String),
+    /// Any other problem, such as a value which was out of range and has been
+    ///     clamped, described by a message.
+    Other(String),
+}
+
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey(key) => write!(f, "Unknown key: {}", key),
+            Self::Deprecated(key) => write!(f, "Deprecated key: {}", key),
+            Self::Other(msg) => f.write_str(msg),
+        }
+    }
+}